@@ -1,50 +1,66 @@
 //! Automatically generated Rust module from SADT diagram.
 //! Module: simple_process
-
-// Placeholder types - Define or import actual types as needed.
+//! Node: A0
+//! Status: working
 #![allow(dead_code, unused_variables, unused_mut)] // Pour le module généré
+#![allow(clippy::doc_lazy_continuation, clippy::let_unit_value)] // Listes de doc compactes, types provisoires `()`
+
+// Placeholder types for arrows without a data dictionary type.
+// Example: type InputData = String;
+pub type InputData = (); // Placeholder
 
-type InputData = (); // Placeholder
-type OutputData = (); // Placeholder
-type ControlParam = (); // Placeholder
-struct MechanismResource; // Placeholder
 
 // --- Function: Traiter Données ---
 
-/// Generated SADT Function: Traiter Données
+/// Generated SADT Function: Traiter Données (box 1, node A1)
 ///
 /// Inputs:
-///   - donnees_brutes (Type: InputData)
+///   - Données Brutes (Type: InputData)
 /// Controls:
 /// Mechanisms:
 /// Outputs:
-///   - donnees_traitées (Type: OutputData)
+///   - Données Traitées (Type: InputData)
 pub fn traiter_donnees(
     donnees_brutes: InputData,
-) -> (OutputData) {
+) -> InputData {
+    // rustsadt:begin a1a1a1a1-b1b1-c1c1-d1d1-e1e1e1e1e1e1 signature:63e6a05a1a0118d5 body:284c864e87cdc976
     // TODO: Implement SADT activity logic for "Traiter Données"
     println!("Executing SADT function: Traiter Données");
 
-    // Placeholder return based on outputs
     todo!("Return actual output(s) for Traiter Données")
+    // rustsadt:end a1a1a1a1-b1b1-c1c1-d1d1-e1e1e1e1e1e1
 }
 
 // --- Function: Générer Rapport ---
 
-/// Generated SADT Function: Générer Rapport
+/// Generated SADT Function: Générer Rapport (box 2, node A2)
 ///
 /// Inputs:
-///   - donnees_traitées (Type: InputData) // Note: Le type est InputData car c'est une entrée pour CE noeud
+///   - Données Traitées (Type: InputData)
 /// Controls:
 /// Mechanisms:
 /// Outputs:
-///   - rapport_final (Type: OutputData) // Supposons une flèche de sortie ajoutée
 pub fn generer_rapport(
-    donnees_traitées: InputData,
-) -> (OutputData) {
+    donnees_traitees: InputData,
+) {
+    // rustsadt:begin a2a2a2a2-b2b2-c2c2-d2d2-e2e2e2e2e2e2 signature:8e92927bf0256ee7 body:e6894344705efa98
     // TODO: Implement SADT activity logic for "Générer Rapport"
     println!("Executing SADT function: Générer Rapport");
 
-    // Placeholder return based on outputs
-    todo!("Return actual output(s) for Générer Rapport")
-}
\ No newline at end of file
+    // No output defined for Générer Rapport
+    // rustsadt:end a2a2a2a2-b2b2-c2c2-d2d2-e2e2e2e2e2e2
+}
+
+// --- Main execution flow ---
+
+/// Runs the activities in data-flow order: each activity is called once all
+/// of its inputs, controls and mechanisms have been produced.
+pub fn run_process(
+    donnees_brutes: InputData,
+) {
+    println!("Running generated SADT process...");
+
+    let donnees_traitees = traiter_donnees(donnees_brutes);
+    generer_rapport(donnees_traitees);
+}
+
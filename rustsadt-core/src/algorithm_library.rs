@@ -0,0 +1,25 @@
+// rustsadt-core/src/algorithm_library.rs
+// Implémentations des algorithmes du registre (`algorithms`). Ce fichier est aussi
+// recopié tel quel dans le code généré: il ne doit dépendre d'aucun autre module.
+
+// Basic arithmetic operations
+pub fn add(a: f64, b: f64) -> f64 {
+    a + b
+}
+
+pub fn subtract(a: f64, b: f64) -> f64 {
+    a - b
+}
+
+pub fn multiply(a: f64, b: f64) -> f64 {
+    a * b
+}
+
+// Division by zero yields NaN (not infinity): the error propagates through later
+// arithmetic instead of passing for a valid result
+pub fn divide(a: f64, b: f64) -> f64 {
+    if b == 0.0 {
+        return f64::NAN;
+    }
+    a / b
+}
@@ -0,0 +1,120 @@
+// rustsadt-core/src/algorithms.rs
+// Registre des algorithmes qu'une activité peut exécuter (champ `ProcessNode::algorithm`).
+// Chaque entrée décrit la signature de la fonction correspondante de `algorithm_library`;
+// le générateur appelle cette fonction à la place du `todo!()` de l'activité.
+use crate::algorithm_library::{add, divide, multiply, subtract};
+use crate::data_dictionary::{DataDictionary, DataTypeKind};
+use crate::sadt_model::{ProcessNode, SadtDiagram};
+
+// Source de la bibliothèque, recopiée dans le code généré (module `algorithms`)
+pub const LIBRARY_SOURCE: &str = include_str!("algorithm_library.rs");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlgorithmParam {
+    pub name: &'static str,
+    pub ty: &'static str, // Type Rust
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Algorithm {
+    pub name: &'static str, // Nom de la fonction dans `algorithm_library`
+    pub description: &'static str,
+    pub params: &'static [AlgorithmParam], // Une flèche d'entrée par paramètre
+    pub output: &'static str, // Type Rust du résultat, transmis sur chaque sortie de l'activité
+    // Exécution par le simulateur: un argument par paramètre, erreur lisible en cas d'échec
+    pub run: fn(&[f64]) -> Result<f64, String>,
+}
+
+const NUMBER_PAIR: &[AlgorithmParam] = &[AlgorithmParam { name: "a", ty: "f64" }, AlgorithmParam { name: "b", ty: "f64" }];
+
+pub const ALGORITHMS: &[Algorithm] = &[
+    Algorithm { name: "add", description: "Somme a + b", params: NUMBER_PAIR, output: "f64", run: |args| Ok(add(args[0], args[1])) },
+    Algorithm { name: "subtract", description: "Différence a - b", params: NUMBER_PAIR, output: "f64", run: |args| Ok(subtract(args[0], args[1])) },
+    Algorithm { name: "multiply", description: "Produit a × b", params: NUMBER_PAIR, output: "f64", run: |args| Ok(multiply(args[0], args[1])) },
+    Algorithm { name: "divide", description: "Quotient a / b (NaN si b est nul)", params: NUMBER_PAIR, output: "f64", run: checked_divide },
+];
+
+// `divide` renvoie NaN pour une division par zéro: le simulateur la signale comme une erreur
+fn checked_divide(args: &[f64]) -> Result<f64, String> {
+    match args {
+        [a, b] => match divide(*a, *b) {
+            quotient if quotient.is_nan() && *b == 0.0 => Err("Division par zéro".to_string()),
+            quotient if quotient.is_nan() => Err("Quotient indéfini (NaN)".to_string()),
+            quotient => Ok(quotient),
+        },
+        _ => Err(format!("2 arguments attendus, {} reçus", args.len())),
+    }
+}
+
+pub fn find_algorithm(name: &str) -> Option<&'static Algorithm> {
+    ALGORITHMS.iter().find(|algorithm| algorithm.name == name)
+}
+
+impl Algorithm {
+    pub fn arity(&self) -> usize {
+        self.params.len()
+    }
+
+    // Signature lisible: "add(a: f64, b: f64) -> f64"
+    pub fn signature(&self) -> String {
+        let params: Vec<String> = self.params.iter().map(|p| format!("{}: {}", p.name, p.ty)).collect();
+        format!("{}({}) -> {}", self.name, params.join(", "), self.output)
+    }
+
+    // Écarts entre la signature et les flèches de l'activité. Les entrées sont associées
+    // aux paramètres dans l'ordre de `SadtDiagram::node_inputs`; une flèche typée doit
+    // référencer un alias du dictionnaire vers le type du paramètre.
+    pub fn signature_issues(&self, diagram: &SadtDiagram, dictionary: &DataDictionary, node: &ProcessNode) -> Vec<String> {
+        let mut issues = Vec::new();
+        let inputs = diagram.node_inputs(node.id);
+        if inputs.len() != self.arity() {
+            issues.push(format!("L'algorithme \"{}\" attend {} entrée(s), l'activité \"{}\" en a {}",
+                self.name, self.arity(), node.name, inputs.len()));
+        }
+        for (arrow, param) in inputs.iter().zip(self.params) {
+            if !type_matches(arrow.data_type.as_deref(), param.ty, dictionary) {
+                issues.push(format!("L'entrée \"{}\" de l'activité \"{}\" n'est pas du type {} attendu par \"{}\" (paramètre {})",
+                    arrow.label.as_deref().unwrap_or("sans label"), node.name, param.ty, self.name, param.name));
+            }
+        }
+
+        let outputs = diagram.node_outputs(node.id);
+        if outputs.is_empty() {
+            issues.push(format!("L'activité \"{}\" n'a aucune sortie pour le résultat de \"{}\"", node.name, self.name));
+        }
+        for arrow in outputs {
+            if !type_matches(arrow.data_type.as_deref(), self.output, dictionary) {
+                issues.push(format!("La sortie \"{}\" de l'activité \"{}\" n'est pas du type {} produit par \"{}\"",
+                    arrow.label.as_deref().unwrap_or("sans label"), node.name, self.output, self.name));
+            }
+        }
+        issues
+    }
+}
+
+// Une flèche sans type (ou d'un type absent du dictionnaire) prend le type de l'algorithme
+fn type_matches(data_type: Option<&str>, ty: &str, dictionary: &DataDictionary) -> bool {
+    match data_type.and_then(|name| dictionary.get(name)) {
+        None => true,
+        Some(data_type) => matches!(&data_type.kind, DataTypeKind::Alias(path) if path.trim() == ty),
+    }
+}
+
+// Algorithme de l'activité, si sa signature correspond aux flèches (sinon le code reste à écrire)
+pub fn applicable_algorithm(diagram: &SadtDiagram, dictionary: &DataDictionary, node: &ProcessNode) -> Option<&'static Algorithm> {
+    find_algorithm(&node.algorithm).filter(|algorithm| algorithm.signature_issues(diagram, dictionary, node).is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn division_by_zero_is_an_error() {
+        assert!(divide(1.0, 0.0).is_nan());
+        let run = find_algorithm("divide").unwrap().run;
+        assert_eq!(run(&[6.0, 3.0]), Ok(2.0));
+        assert_eq!(run(&[1.0, 0.0]), Err("Division par zéro".to_string()));
+        assert!(run(&[f64::NAN, 2.0]).is_err());
+    }
+}
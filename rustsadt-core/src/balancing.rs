@@ -0,0 +1,85 @@
+// rustsadt-core/src/balancing.rs
+// Équilibrage parent/enfant: les flèches qui touchent une boîte décomposée
+// doivent se retrouver comme flèches frontières (codes ICOM) dans son sous-diagramme.
+use crate::sadt_elements::{ArrowId, ArrowType, IcomCode, NodeId};
+use crate::sadt_model::SadtDiagram;
+use std::collections::HashSet;
+
+// Flèche du diagramme parent attachée à la boîte décomposée, avec son code ICOM
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParentIcom {
+    pub code: IcomCode,
+    pub arrow_id: ArrowId,
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceIssueKind {
+    Missing, // Présent sur la boîte parente, absent du sous-diagramme
+    Extra,   // Présent dans le sous-diagramme, absent de la boîte parente
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceIssue {
+    pub node_id: NodeId, // Boîte parente décomposée
+    pub node_name: String,
+    pub kind: BalanceIssueKind,
+    pub code: IcomCode,
+    pub label: Option<String>,
+}
+
+impl std::fmt::Display for BalanceIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = self.label.as_deref().map(|l| format!(" \"{}\"", l)).unwrap_or_default();
+        match self.kind {
+            BalanceIssueKind::Missing => write!(
+                f, "{} {}{} de \"{}\": aucune flèche correspondante dans le sous-diagramme",
+                self.code.arrow_type.label(), self.code, label, self.node_name
+            ),
+            BalanceIssueKind::Extra => write!(
+                f, "{} {}{} en trop dans le sous-diagramme de \"{}\"",
+                self.code.arrow_type.label(), self.code, label, self.node_name
+            ),
+        }
+    }
+}
+
+// Calcule les codes ICOM des flèches attachées à une boîte du diagramme parent.
+// Le rôle dépend du côté d'attachement; la numérotation suit l'ordre des
+// emplacements sur le côté (de haut en bas pour I/O, de gauche à droite pour C/M).
+pub fn parent_icom_codes(parent: &SadtDiagram, node_id: NodeId) -> Vec<ParentIcom> {
+    let mut codes = Vec::new();
+    for role in [ArrowType::Input, ArrowType::Control, ArrowType::Output, ArrowType::Mechanism] {
+        for (index, arrow_id) in parent.side_arrows(node_id, role.attachment_side()).into_iter().enumerate() {
+            let label = parent.get_arrow(arrow_id).and_then(|arrow| arrow.label.clone());
+            codes.push(ParentIcom { code: IcomCode::new(role, index as u32 + 1), arrow_id, label });
+        }
+    }
+    codes
+}
+
+// Compare les flèches de la boîte parente avec les flèches frontières du sous-diagramme
+pub fn check_balance(parent: &SadtDiagram, node_id: NodeId, child: &SadtDiagram) -> Vec<BalanceIssue> {
+    let node_name = parent.get_node(node_id).map(|n| n.name.clone()).unwrap_or_default();
+    let expected = parent_icom_codes(parent, node_id);
+    let present: HashSet<IcomCode> = child.boundary_arrows().filter_map(|a| a.icom()).collect();
+    let expected_codes: HashSet<IcomCode> = expected.iter().map(|p| p.code).collect();
+
+    let mut issues: Vec<BalanceIssue> = expected.iter()
+        .filter(|p| !present.contains(&p.code))
+        .map(|p| BalanceIssue {
+            node_id, node_name: node_name.clone(), kind: BalanceIssueKind::Missing, code: p.code, label: p.label.clone(),
+        })
+        .collect();
+
+    let mut extra: Vec<_> = child.boundary_arrows()
+        .filter(|a| a.icom().is_some_and(|code| !expected_codes.contains(&code)))
+        .map(|a| (a.icom().unwrap(), a.label.clone()))
+        .collect();
+    extra.sort_by_key(|(code, _)| (code.arrow_type.icom_prefix(), code.index));
+    extra.dedup_by_key(|(code, _)| *code);
+    issues.extend(extra.into_iter().map(|(code, label)| BalanceIssue {
+        node_id, node_name: node_name.clone(), kind: BalanceIssueKind::Extra, code, label,
+    }));
+    issues
+}
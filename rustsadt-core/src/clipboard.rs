@@ -0,0 +1,151 @@
+// rustsadt-core/src/clipboard.rs
+// Fragments de diagramme pour le copier-coller: des nœuds (avec leurs sous-diagrammes)
+// et les flèches qui les relient. Le fragment s'échange sous forme de texte RON, comme
+// les fichiers .ron (le JSON équivalent est aussi accepté au collage), ce qui permet de
+// passer par le presse-papiers du système d'une fenêtre ou d'un fichier à l'autre.
+use crate::error::Result;
+use crate::geometry::{Rect, Vec2};
+use crate::numbering::next_free_number;
+use crate::sadt_elements::{ArrowEnd, ArrowId, NodeId};
+use crate::sadt_model::{Arrow, ProcessNode, SadtDiagram};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiagramFragment {
+    pub nodes: Vec<ProcessNode>,
+    pub arrows: Vec<Arrow>,
+}
+
+impl DiagramFragment {
+    // Nœuds désignés et flèches dont les deux extrémités sont parmi eux
+    // (ou sur une flèche copiée, pour les embranchements et jonctions)
+    pub fn copy(diagram: &SadtDiagram, node_ids: &[NodeId]) -> Self {
+        let mut nodes: Vec<ProcessNode> = node_ids.iter().filter_map(|id| diagram.get_node(*id)).cloned().collect();
+        let copied = |end: &ArrowEnd, arrows: &[Arrow]| match end {
+            ArrowEnd::Node(cp) => node_ids.contains(&cp.node_id),
+            ArrowEnd::Junction(id) => arrows.iter().any(|arrow| arrow.id == *id),
+            ArrowEnd::Boundary(_) => false,
+        };
+        let mut arrows: Vec<Arrow> = Vec::new();
+        loop {
+            let more: Vec<Arrow> = diagram.arrows.values()
+                .filter(|arrow| !arrows.iter().any(|other| other.id == arrow.id))
+                .filter(|arrow| copied(&arrow.source, &arrows) && copied(&arrow.target, &arrows))
+                .cloned()
+                .collect();
+            if more.is_empty() { break; }
+            arrows.extend(more);
+        }
+        // Texte identique pour une même sélection (les HashMap du diagramme n'ont pas d'ordre)
+        nodes.sort_by_key(|node| node.id);
+        arrows.sort_by_key(|arrow| arrow.id);
+        DiagramFragment { nodes, arrows }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    // Rectangle englobant les nœuds du fragment
+    pub fn bounds(&self) -> Rect {
+        self.nodes.iter().fold(Rect::NOTHING, |acc, node| acc.union(node.rect))
+    }
+
+    pub fn to_text(&self) -> Result<String> {
+        Ok(ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?)
+    }
+
+    pub fn from_text(text: &str) -> Result<Self> {
+        Ok(ron::from_str(text).or_else(|ron_error| serde_json::from_str(text).map_err(|_| ron_error))?)
+    }
+
+    // Ajoute le fragment au diagramme, décalé de `offset`, avec de nouveaux identifiants
+    // (sous-diagrammes compris: chaque activité garde un UUID unique, utilisé par le code généré).
+    // Les nœuds collés prennent les numéros libres du diagramme, dans l'ordre de leurs numéros
+    // d'origine. Retourne les nœuds et flèches créés.
+    pub fn paste_into(&self, diagram: &mut SadtDiagram, offset: Vec2) -> (Vec<NodeId>, Vec<ArrowId>) {
+        let mut pasted = renew_ids(self.nodes.clone(), self.arrows.clone());
+        let node_ids = pasted.nodes.iter().map(|node| node.id).collect();
+        let arrow_ids = pasted.arrows.iter().map(|arrow| arrow.id).collect();
+        pasted.nodes.sort_by_key(|node| (node.number.is_none(), node.number));
+        for mut node in pasted.nodes {
+            node.rect = node.rect.translate(offset);
+            node.number = Some(next_free_number(diagram));
+            diagram.nodes.insert(node.id, node);
+        }
+        for mut arrow in pasted.arrows {
+            for waypoint in &mut arrow.waypoints { *waypoint += offset; }
+            diagram.arrows.insert(arrow.id, arrow);
+        }
+        (node_ids, arrow_ids)
+    }
+}
+
+fn renew_ids(nodes: Vec<ProcessNode>, arrows: Vec<Arrow>) -> DiagramFragment {
+    let new_ids: HashMap<NodeId, NodeId> = nodes.iter().map(|node| (node.id, Uuid::new_v4())).collect();
+    let nodes = nodes.into_iter()
+        .map(|mut node| {
+            node.id = new_ids[&node.id];
+            node.child_diagram = node.child_diagram.map(|child| {
+                let fragment = renew_ids(child.nodes.into_values().collect(), child.arrows.into_values().collect());
+                SadtDiagram {
+                    nodes: fragment.nodes.into_iter().map(|n| (n.id, n)).collect(),
+                    arrows: fragment.arrows.into_iter().map(|a| (a.id, a)).collect(),
+                    ..child
+                }
+            });
+            node
+        })
+        .collect();
+    let new_arrow_ids: HashMap<ArrowId, ArrowId> = arrows.iter().map(|arrow| (arrow.id, Uuid::new_v4())).collect();
+    let arrows = arrows.into_iter()
+        .map(|mut arrow| {
+            arrow.id = new_arrow_ids[&arrow.id];
+            for end in [&mut arrow.source, &mut arrow.target] {
+                match end {
+                    ArrowEnd::Node(cp) => {
+                        if let Some(id) = new_ids.get(&cp.node_id) { cp.node_id = *id; }
+                    }
+                    ArrowEnd::Junction(trunk) => {
+                        if let Some(id) = new_arrow_ids.get(trunk) { *trunk = *id; }
+                    }
+                    ArrowEnd::Boundary(_) => {}
+                }
+            }
+            arrow
+        })
+        .collect();
+    DiagramFragment { nodes, arrows }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{vec2, Point};
+    use crate::sadt_elements::{ArrowType, ConnectionPoint, Side};
+    use std::collections::HashSet;
+
+    // Duplication d'une sélection numérotée: numéros libres, nouveaux identifiants, liens conservés
+    #[test]
+    fn pasted_nodes_get_new_ids_and_free_numbers() {
+        let mut diagram = SadtDiagram::new();
+        let first = diagram.add_node("Lire".to_string(), Point::new(0.0, 0.0));
+        let second = diagram.add_node("Trier".to_string(), Point::new(300.0, 0.0));
+        let trunk = diagram.add_arrow(ConnectionPoint::new(first, Side::Right), ConnectionPoint::new(second, Side::Left), ArrowType::Output, None).unwrap();
+        diagram.add_arrow(ArrowEnd::Junction(trunk), ConnectionPoint::new(second, Side::Top), ArrowType::Control, None).unwrap();
+
+        let fragment = DiagramFragment::from_text(&DiagramFragment::copy(&diagram, &[first, second]).to_text().unwrap()).unwrap();
+        let (nodes, arrows) = fragment.paste_into(&mut diagram, vec2(0.0, 200.0));
+
+        assert!(nodes.iter().all(|id| *id != first && *id != second));
+        let numbers: HashSet<Option<u32>> = diagram.nodes.values().map(|node| node.number).collect();
+        assert_eq!(numbers, HashSet::from([Some(1), Some(2), Some(3), Some(4)]));
+        for id in &arrows {
+            let arrow = diagram.get_arrow(*id).unwrap();
+            assert!(arrow.source.node_id().is_none_or(|node| nodes.contains(&node)));
+            assert!(arrow.source.junction().is_none_or(|trunk| arrows.contains(&trunk)));
+        }
+    }
+}
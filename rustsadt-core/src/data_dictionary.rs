@@ -0,0 +1,212 @@
+// rustsadt-core/src/data_dictionary.rs
+// Dictionnaire de données: types nommés que les flèches peuvent référencer.
+// Il est porté par le diagramme racine et partagé par tous les sous-diagrammes;
+// le code généré déclare ces types (ou les importe pour les alias).
+use serde::{Deserialize, Serialize};
+use syn::{GenericArgument, PathArguments, Type};
+
+// Types de la bibliothèque standard qui implémentent `Clone`, et conteneurs qui
+// l'implémentent quand leurs paramètres l'implémentent
+const CLONE_TYPES: [&str; 20] = [
+    "bool", "char", "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
+    "f32", "f64", "String", "PathBuf", "OsString", "Duration",
+];
+const CLONE_CONTAINERS: [&str; 9] = ["Vec", "VecDeque", "Option", "Result", "Box", "HashMap", "HashSet", "BTreeMap", "BTreeSet"];
+
+const RUST_KEYWORDS: [&str; 38] = [
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+    "self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataDictionary {
+    pub types: Vec<DataType>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataType {
+    pub name: String, // Nom du type Rust (ex: "Commande")
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub kind: DataTypeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DataTypeKind {
+    Struct(Vec<DataField>),
+    Enum(Vec<String>), // Variantes sans données
+    Alias(String),     // Chemin d'un type Rust existant (ex: "std::path::PathBuf")
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataField {
+    pub name: String,
+    pub ty: String, // Type Rust du champ (peut référencer un autre type du dictionnaire)
+}
+
+impl DataDictionary {
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DataType> {
+        self.types.iter().find(|data_type| data_type.name == name)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    // Nom libre pour un nouveau type ("Donnee1", "Donnee2", ...)
+    pub fn unused_name(&self) -> String {
+        (1..).map(|i| format!("Donnee{}", i)).find(|name| !self.contains(name)).unwrap_or_default()
+    }
+
+    // `ty` implémente `Clone` à coup sûr: types du dictionnaire dont tous les composants
+    // l'implémentent, types usuels de la bibliothèque standard. Un type inconnu est
+    // supposé ne pas l'implémenter.
+    pub fn is_clone(&self, ty: &str) -> bool {
+        syn::parse_str::<Type>(ty).is_ok_and(|ty| self.type_is_clone(&ty, &mut Vec::new()))
+    }
+
+    // `visiting`: types du dictionnaire en cours d'examen (un type récursif ne se décide
+    // que par ses autres composants)
+    fn type_is_clone(&self, ty: &Type, visiting: &mut Vec<String>) -> bool {
+        match ty {
+            Type::Reference(reference) => reference.mutability.is_none(),
+            Type::Tuple(tuple) => tuple.elems.iter().all(|elem| self.type_is_clone(elem, visiting)),
+            Type::Array(array) => self.type_is_clone(&array.elem, visiting),
+            Type::Paren(paren) => self.type_is_clone(&paren.elem, visiting),
+            Type::Path(path) if path.qself.is_none() => {
+                let Some(segment) = path.path.segments.last() else { return false };
+                let name = segment.ident.to_string();
+                let args: Vec<&Type> = match &segment.arguments {
+                    PathArguments::None => Vec::new(),
+                    PathArguments::AngleBracketed(generics) => generics.args.iter()
+                        .filter_map(|arg| match arg { GenericArgument::Type(ty) => Some(ty), _ => None })
+                        .collect(),
+                    PathArguments::Parenthesized(_) => return false,
+                };
+                match self.get(&name) {
+                    Some(data_type) if path.path.segments.len() == 1 => self.data_type_is_clone(data_type, visiting),
+                    _ if name == "Rc" || name == "Arc" => true,
+                    _ if CLONE_TYPES.contains(&name.as_str()) => args.is_empty(),
+                    _ if CLONE_CONTAINERS.contains(&name.as_str()) => args.iter().all(|arg| self.type_is_clone(arg, visiting)),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    fn data_type_is_clone(&self, data_type: &DataType, visiting: &mut Vec<String>) -> bool {
+        if visiting.contains(&data_type.name) { return true; }
+        visiting.push(data_type.name.clone());
+        let parses_as_clone = |ty: &str, visiting: &mut Vec<String>| {
+            syn::parse_str::<Type>(ty).is_ok_and(|ty| self.type_is_clone(&ty, visiting))
+        };
+        let clone = match &data_type.kind {
+            DataTypeKind::Struct(fields) => fields.iter().all(|field| parses_as_clone(&field.ty, visiting)),
+            DataTypeKind::Enum(_) => true, // Variantes sans données, toujours dérivées `Clone`
+            DataTypeKind::Alias(path) => parses_as_clone(path, visiting),
+        };
+        visiting.pop();
+        clone
+    }
+
+    // Problèmes empêchant de générer du code valide (noms invalides, doublons, alias vides)
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        for (index, data_type) in self.types.iter().enumerate() {
+            let name = &data_type.name;
+            if !is_rust_ident(name) {
+                problems.push(format!("Le type \"{}\" n'est pas un identifiant Rust valide", name));
+            } else if self.types[..index].iter().any(|other| other.name == *name) {
+                problems.push(format!("Le type \"{}\" est défini plusieurs fois", name));
+            }
+            match &data_type.kind {
+                DataTypeKind::Struct(fields) => {
+                    for (i, field) in fields.iter().enumerate() {
+                        if !is_rust_ident(&field.name) {
+                            problems.push(format!("Le champ \"{}\" de \"{}\" n'est pas un identifiant Rust valide", field.name, name));
+                        } else if fields[..i].iter().any(|other| other.name == field.name) {
+                            problems.push(format!("Le champ \"{}\" de \"{}\" est défini plusieurs fois", field.name, name));
+                        }
+                        if field.ty.trim().is_empty() {
+                            problems.push(format!("Le champ \"{}\" de \"{}\" n'a pas de type", field.name, name));
+                        }
+                    }
+                }
+                DataTypeKind::Enum(variants) => {
+                    if variants.is_empty() {
+                        problems.push(format!("L'énumération \"{}\" n'a aucune variante", name));
+                    }
+                    for (i, variant) in variants.iter().enumerate() {
+                        if !is_rust_ident(variant) {
+                            problems.push(format!("La variante \"{}\" de \"{}\" n'est pas un identifiant Rust valide", variant, name));
+                        } else if variants[..i].contains(variant) {
+                            problems.push(format!("La variante \"{}\" de \"{}\" est définie plusieurs fois", variant, name));
+                        }
+                    }
+                }
+                DataTypeKind::Alias(path) => {
+                    if path.trim().is_empty() {
+                        problems.push(format!("L'alias \"{}\" ne désigne aucun type", name));
+                    }
+                }
+            }
+        }
+        problems
+    }
+}
+
+impl DataTypeKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DataTypeKind::Struct(_) => "Structure",
+            DataTypeKind::Enum(_) => "Énumération",
+            DataTypeKind::Alias(_) => "Alias",
+        }
+    }
+}
+
+// Identifiant Rust ASCII qui n'est pas un mot-clé
+pub fn is_rust_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    valid_start && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') && name != "_" && !is_rust_keyword(name)
+}
+
+pub fn is_rust_keyword(name: &str) -> bool {
+    RUST_KEYWORDS.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_type(name: &str, kind: DataTypeKind) -> DataType {
+        DataType { name: name.to_string(), description: String::new(), kind }
+    }
+
+    fn field(name: &str, ty: &str) -> DataField {
+        DataField { name: name.to_string(), ty: ty.to_string() }
+    }
+
+    #[test]
+    fn clone_follows_components() {
+        let dictionary = DataDictionary { types: vec![
+            data_type("Commande", DataTypeKind::Struct(vec![field("lignes", "Vec<(String, u32)>"), field("suite", "Option<Box<Commande>>")])),
+            data_type("Fichier", DataTypeKind::Alias("std::fs::File".to_string())),
+            data_type("Lot", DataTypeKind::Struct(vec![field("source", "Fichier"), field("taille", "usize")])),
+            data_type("Etat", DataTypeKind::Enum(vec!["Ouvert".to_string()])),
+        ] };
+        assert!(dictionary.is_clone("Commande"));
+        assert!(dictionary.is_clone("std::collections::HashMap<String, Etat>"));
+        assert!(dictionary.is_clone("&Lot") && dictionary.is_clone("std::sync::Arc<Lot>"));
+        assert!(!dictionary.is_clone("Fichier"));
+        assert!(!dictionary.is_clone("Lot"));
+        assert!(!dictionary.is_clone("Vec<Lot>") && !dictionary.is_clone("&mut String"));
+    }
+}
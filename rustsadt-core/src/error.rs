@@ -15,6 +15,18 @@ pub enum RustSadtError {
     #[error("Erreur de rendu du template Tera: {0}")]
     Tera(#[from] tera::Error),
 
+    #[error("Erreur de lecture SVG: {0}")]
+    Svg(#[from] usvg::Error),
+
+    #[error("Erreur d'encodage PNG: {0}")]
+    Png(#[from] png::EncodingError),
+
+    #[error("Erreur d'analyse du code Rust: {0}")]
+    RustParse(#[from] syn::Error),
+
+    #[error("Erreur d'export: {0}")]
+    Export(String),
+
     #[error("Impossible de trouver le répertoire home")]
     HomeDir,
 
@@ -24,6 +36,9 @@ pub enum RustSadtError {
     #[error("Élément non trouvé avec l'ID: {0}")]
     NotFound(String),
 
+    #[error("Validation échouée: {0} problème(s)")]
+    Validation(usize),
+
     #[error("Erreur de génération: {0}")]
     Generation(String),
 
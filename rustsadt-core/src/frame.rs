@@ -0,0 +1,171 @@
+// rustsadt-core/src/frame.rs
+// Cartouche IDEF0 autour du cadre d'un diagramme: un en-tête (auteur, projet, date,
+// révision, statut, contexte) et un pied (nœud, titre, numéro C). La géométrie est
+// calculée ici une fois pour le canvas et pour les exports SVG/PNG.
+use crate::geometry::{vec2, Point, Rect};
+use crate::numbering::box_numbers;
+use crate::sadt_elements::NodeId;
+use crate::sadt_model::{DiagramMetadata, DiagramStatus, SadtDiagram};
+use crate::style::{text_width, FRAME_CAPTION_FONT_SIZE, FRAME_VALUE_FONT_SIZE};
+
+const HEADER_HEIGHT: f32 = 60.0;
+const FOOTER_HEIGHT: f32 = 32.0;
+const MIN_FORM_WIDTH: f32 = 480.0; // En deçà, le cartouche déborde du cadre pour rester lisible
+const CELL_PADDING: f32 = 3.0;
+const MARK_SIZE: f32 = 7.0; // Case à cocher du statut
+
+// Référence IDEF0 d'un diagramme et de son diagramme parent (le contexte)
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiagramReference {
+    pub node: String,
+    pub context: Option<String>,
+}
+
+impl DiagramReference {
+    // Un diagramme racine à une seule boîte est le diagramme de contexte A-0,
+    // sinon la racine est directement A0
+    pub fn root(root: &SadtDiagram) -> Self {
+        let computed = if root.nodes.len() == 1 { "A-0" } else { "A0" };
+        DiagramReference { node: effective(root, computed.to_string()), context: None }
+    }
+
+    // Référence du sous-diagramme de `node_id`, nœud de `parent` (diagramme de cette référence)
+    pub fn child(&self, parent: &SadtDiagram, node_id: NodeId, child: &SadtDiagram) -> Self {
+        let number = box_numbers(parent).get(&node_id).copied().unwrap_or_default();
+        DiagramReference { node: effective(child, self.box_reference(number)), context: Some(self.node.clone()) }
+    }
+
+    // Référence de la boîte numéro `number` de ce diagramme (A0 > A2 > A21...).
+    // La boîte unique du diagramme de contexte A-0 est A0.
+    pub fn box_reference(&self, number: u32) -> String {
+        match self.node.as_str() {
+            "A-0" => "A0".to_string(),
+            "A0" => format!("A{}", number),
+            parent => format!("{}{}", parent, number),
+        }
+    }
+
+    // Référence d'un diagramme à partir de ses ancêtres (de la racine au parent,
+    // avec le nœud décomposé de chacun)
+    pub fn along<'a>(ancestors: impl IntoIterator<Item = (&'a SadtDiagram, NodeId)>, diagram: &SadtDiagram) -> Self {
+        let mut ancestors = ancestors.into_iter().peekable();
+        let Some((root, _)) = ancestors.peek() else { return DiagramReference::root(diagram) };
+        let mut reference = DiagramReference::root(root);
+        while let Some((parent, node_id)) = ancestors.next() {
+            let child = ancestors.peek().map_or(diagram, |(next, _)| *next);
+            reference = reference.child(parent, node_id, child);
+        }
+        reference
+    }
+}
+
+// Référence choisie par l'utilisateur, sinon celle déduite de la hiérarchie
+fn effective(diagram: &SadtDiagram, computed: String) -> String {
+    let chosen = diagram.metadata.node_reference.trim();
+    if chosen.is_empty() { computed } else { chosen.to_string() }
+}
+
+// Case du cartouche: intitulé en petit, valeur en dessous
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameCell {
+    pub rect: Rect,
+    pub caption: &'static str,
+    pub value: String,
+}
+
+impl FrameCell {
+    // Coins supérieurs gauches des deux textes
+    pub fn caption_pos(&self) -> Point {
+        self.rect.min + vec2(CELL_PADDING, CELL_PADDING)
+    }
+
+    pub fn value_pos(&self) -> Point {
+        self.caption_pos() + vec2(0.0, FRAME_CAPTION_FONT_SIZE + CELL_PADDING)
+    }
+}
+
+// Case à cocher d'un statut, son libellé à droite
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusMark {
+    pub rect: Rect,
+    pub label: &'static str,
+    pub checked: bool,
+}
+
+impl StatusMark {
+    pub fn label_pos(&self) -> Point {
+        Point::new(self.rect.right() + CELL_PADDING, self.rect.top() - 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Idef0Frame {
+    pub outer: Rect, // Contour du formulaire, cadre du diagramme compris
+    pub cells: Vec<FrameCell>,
+    pub marks: Vec<StatusMark>,
+}
+
+impl Idef0Frame {
+    pub fn new(frame: Rect, metadata: &DiagramMetadata, reference: &DiagramReference) -> Self {
+        let width = frame.width().max(MIN_FORM_WIDTH);
+        let left = frame.center().x - width / 2.0;
+        let header = Rect::from_min_size(Point::new(left, frame.top() - HEADER_HEIGHT), vec2(width, HEADER_HEIGHT));
+        let footer = Rect::from_min_size(Point::new(left, frame.bottom()), vec2(width, FOOTER_HEIGHT));
+        // Colonne entre deux fractions de la largeur, sur une bande
+        let column = |band: Rect, from: f32, to: f32| {
+            Rect::from_min_max(Point::new(band.left() + width * from, band.top()), Point::new(band.left() + width * to, band.bottom()))
+        };
+        let stacked = |column: Rect| {
+            let middle = column.center().y;
+            (Rect::from_min_max(column.min, Point::new(column.right(), middle)), Rect::from_min_max(Point::new(column.left(), middle), column.max))
+        };
+        let cell = |rect: Rect, caption: &'static str, value: &str| {
+            let max_width = rect.width() - 2.0 * CELL_PADDING;
+            FrameCell { rect, caption, value: truncate(value, FRAME_VALUE_FONT_SIZE, max_width) }
+        };
+
+        let (author, project) = stacked(column(header, 0.0, 0.4));
+        let (date, revision) = stacked(column(header, 0.4, 0.6));
+        let status = column(header, 0.6, 0.8);
+        let context = column(header, 0.8, 1.0);
+        let context_value = reference.context.as_deref().unwrap_or("Racine");
+        let cells = vec![
+            cell(author, "AUTEUR", &metadata.author),
+            cell(project, "PROJET", &metadata.project),
+            cell(date, "DATE", &metadata.date),
+            cell(revision, "RÉV.", &metadata.revision),
+            FrameCell { rect: status, caption: "", value: String::new() },
+            cell(context, "CONTEXTE", context_value),
+            cell(column(footer, 0.0, 0.2), "NŒUD", &reference.node),
+            cell(column(footer, 0.2, 0.75), "TITRE", &metadata.title),
+            cell(column(footer, 0.75, 1.0), "NUMÉRO", &metadata.c_number),
+        ];
+
+        // Une ligne par statut, la case du statut courant cochée
+        let row_height = HEADER_HEIGHT / DiagramStatus::ALL.len() as f32;
+        let marks = DiagramStatus::ALL.iter().enumerate()
+            .map(|(index, status_choice)| {
+                let center_y = status.top() + row_height * (index as f32 + 0.5);
+                StatusMark {
+                    rect: Rect::from_center_size(Point::new(status.left() + CELL_PADDING + MARK_SIZE / 2.0, center_y), vec2(MARK_SIZE, MARK_SIZE)),
+                    label: status_choice.label(),
+                    checked: *status_choice == metadata.status,
+                }
+            })
+            .collect();
+
+        Idef0Frame { outer: header.union(footer).union(frame), cells, marks }
+    }
+}
+
+// Texte raccourci (points de suspension) pour tenir dans `max_width`
+fn truncate(text: &str, font_size: f32, max_width: f32) -> String {
+    if text_width(text, font_size) <= max_width {
+        return text.to_string();
+    }
+    let mut shortened: String = text.to_string();
+    while !shortened.is_empty() && text_width(&format!("{}…", shortened), font_size) > max_width {
+        shortened.pop();
+    }
+    format!("{}…", shortened.trim_end())
+}
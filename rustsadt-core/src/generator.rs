@@ -0,0 +1,655 @@
+use crate::algorithms::{applicable_algorithm, LIBRARY_SOURCE};
+use crate::error::Result; // Utilise l'alias Result<T> = std::result::Result<T, RustSadtError>
+use crate::error::RustSadtError;
+use crate::data_dictionary::{is_rust_keyword, DataDictionary, DataField, DataTypeKind};
+use crate::frame::DiagramReference;
+use crate::numbering::{box_numbers, numbered_order};
+use crate::pipeline::execution_order;
+use crate::protected::{self, MergeReport};
+use crate::sadt_model::{Arrow, DiagramMetadata, DiagramStatus, SadtDiagram};
+use crate::sadt_elements::{ArrowId, ArrowType, NodeId};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tera::{Context, Tera};
+
+const TEMPLATE_DIR: &str = "templates"; // Relatif au répertoire courant
+pub(crate) const LIBRARY_MODULE: &str = "algorithms"; // Bibliothèque d'algorithmes embarquée
+// Types utilisés pour les flèches sans type du dictionnaire, dans leur ordre de déclaration
+pub(crate) const PLACEHOLDER_TYPES: [&str; 4] = ["InputData", "OutputData", "ControlParam", "MechanismResource"];
+
+
+// Donnée portée par une flèche: paramètre ou valeur de retour d'une fonction générée
+#[derive(Serialize)]
+struct ParamContext {
+    name: String,      // Identifiant Rust (unique dans le module)
+    label: String,     // Label de la flèche
+    ty: String,        // Type du dictionnaire, ou type placeholder
+    by_ref: bool,      // Mécanismes passés par référence
+}
+
+// Structure pour passer les données au template Tera (Fonction)
+#[derive(Serialize)]
+struct FunctionContext<'a> {
+    name: &'a str,
+    id: String, // UUID du nœud, clé de la région protégée du corps
+    ident: String,
+    number: u32,       // Numéro de boîte
+    reference: String, // Référence IDEF0 de l'activité (A12...)
+    inputs: Vec<ParamContext>,
+    outputs: Vec<ParamContext>,
+    controls: Vec<ParamContext>,
+    mechanisms: Vec<ParamContext>,
+    return_type: String,
+    call: String, // Appel de l'algorithme choisi (vide: corps à écrire)
+}
+
+// Appel d'une activité dans `run_process`
+#[derive(Serialize)]
+struct StepContext {
+    function: String,
+    bindings: String, // Motif recevant les sorties (vide si aucune)
+    args: String,
+}
+
+// Enchaînement des activités dans l'ordre des dépendances
+#[derive(Serialize)]
+struct PipelineContext {
+    params: Vec<ParamContext>, // Flèches entrant par le cadre du diagramme
+    steps: Vec<StepContext>,
+    return_type: String,
+    result: String, // Flèches sortant par le cadre du diagramme
+}
+
+// Type du dictionnaire de données déclaré par le module racine
+#[derive(Serialize)]
+struct DataTypeContext<'a> {
+    name: &'a str,
+    description: &'a str,
+    kind: &'static str, // "struct", "enum" ou "alias"
+    fields: &'a [DataField],
+    variants: &'a [String],
+    path: &'a str,
+    clone: bool, // Structure dérivée `Clone` (tous ses champs le sont)
+}
+
+// Sous-module généré pour le sous-diagramme d'une activité décomposée
+#[derive(Serialize)]
+struct SubmoduleContext {
+    name: String,
+    parent_name: String,
+    code: String,
+}
+
+// Métadonnées d'un niveau de la hiérarchie (cartouche IDEF0)
+#[derive(Serialize)]
+struct MetadataContext<'a> {
+    node_reference: String, // Référence effective (choisie ou déduite de la hiérarchie)
+    title: &'a str,
+    description: &'a str,
+    author: &'a str,
+    project: &'a str,
+    date: &'a str,
+    revision: &'a str,
+    status: &'static str,       // Clé stable: working, draft, recommended, publication
+    status_label: &'static str, // Libellé affiché dans l'éditeur
+    c_number: &'a str,
+}
+
+impl<'a> MetadataContext<'a> {
+    fn new(metadata: &'a DiagramMetadata, reference: &DiagramReference) -> Self {
+        let status = match metadata.status {
+            DiagramStatus::Working => "working",
+            DiagramStatus::Draft => "draft",
+            DiagramStatus::Recommended => "recommended",
+            DiagramStatus::Publication => "publication",
+        };
+        MetadataContext {
+            node_reference: reference.node.clone(),
+            title: &metadata.title,
+            description: &metadata.description,
+            author: &metadata.author,
+            project: &metadata.project,
+            date: &metadata.date,
+            revision: &metadata.revision,
+            status,
+            status_label: metadata.status.label(),
+            c_number: &metadata.c_number,
+        }
+    }
+}
+
+// Structure pour passer les données au template Tera (Module)
+#[derive(Serialize)]
+struct ModuleContext<'a> {
+    module_name: &'a str,
+    metadata: MetadataContext<'a>,
+    data_types: Vec<DataTypeContext<'a>>, // Vide dans les sous-modules
+    import_parent: bool, // Les sous-modules importent les types du dictionnaire
+    algorithm_library: &'static str, // Source de `algorithm_library`, module racine seulement
+    placeholders: Vec<&'static str>,
+    functions: Vec<FunctionContext<'a>>,
+    pipeline: PipelineContext,
+    submodules: Vec<SubmoduleContext>,
+}
+
+// Structure spécifique pour le contexte Markdown
+#[derive(Serialize)]
+struct MarkdownNodeContext {
+    id: String,
+    number: u32,
+    reference: String,
+    name: String,
+    description: String,
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+}
+
+// Un niveau de la hiérarchie (diagramme racine ou sous-diagramme)
+#[derive(Serialize)]
+struct MarkdownDiagramContext<'a> {
+    title: String,
+    metadata: MetadataContext<'a>,
+    parent_name: Option<String>,
+    depth: usize,
+    nodes: Vec<MarkdownNodeContext>,
+}
+
+// Structure spécifique pour le contexte Markdown (Document)
+#[derive(Serialize)]
+struct MarkdownDocContext<'a> {
+     metadata: MetadataContext<'a>, // Diagramme racine
+     data_types: Vec<DataTypeContext<'a>>,
+     diagrams: Vec<MarkdownDiagramContext<'a>>,
+}
+
+
+pub struct CodeGenerator {
+    tera: Tera,
+}
+
+impl CodeGenerator {
+    pub fn new() -> Result<Self> { // Retourne Result<CodeGenerator, RustSadtError>
+        Self::from_dir(Path::new(TEMPLATE_DIR))
+    }
+
+    // Charge les templates depuis un autre dossier (ex: CLI lancée hors de la racine du projet)
+    pub fn from_dir(dir: &Path) -> Result<Self> {
+        let pattern = dir.join("**").join("*");
+        let mut tera = match Tera::new(&pattern.to_string_lossy()) {
+            Ok(t) => t,
+            Err(e) => {
+                 log::error!("Erreur lors du chargement des templates Tera depuis '{}': {}", dir.display(), e);
+                 // Essayer de donner plus de contexte
+                 if let Ok(cwd) = std::env::current_dir() {
+                    log::error!("Répertoire courant: {}", cwd.display());
+                 } else {
+                     log::error!("Impossible de déterminer le répertoire courant.");
+                 }
+                 log::error!("Assurez-vous que le dossier 'templates' existe à la racine du projet et contient les fichiers .tera, et que vous lancez l'exécutable depuis la racine.");
+                return Err(e.into()); // Convertit tera::Error en RustSadtError::Tera
+            }
+        };
+        if tera.get_template_names().next().is_none() {
+            return Err(RustSadtError::Generation(format!("Aucun template trouvé dans '{}'", dir.display())));
+        }
+        tera.autoescape_on(vec![]);
+        Ok(CodeGenerator { tera })
+    }
+
+    pub fn generate_rust_module(&self, diagram: &SadtDiagram, module_name: &str) -> Result<String> {
+        let code = self.render_module(diagram, &DiagramReference::root(diagram), module_name, &diagram.data_dictionary, 0)?;
+        Ok(protected::seal(&code))
+    }
+
+    // Régénère dans le contenu d'un fichier déjà généré: les corps modifiés à la main
+    // sont conservés, signatures et commentaires sont mis à jour
+    pub fn regenerate_rust_module(&self, diagram: &SadtDiagram, module_name: &str, existing: &str) -> Result<(String, MergeReport)> {
+        let code = self.generate_rust_module(diagram, module_name)?;
+        protected::merge(existing, &code)
+    }
+
+    // Les sous-diagrammes sont générés avec le dictionnaire de la racine,
+    // `depth` sous-modules en dessous du module racine
+    fn render_module(&self, diagram: &SadtDiagram, reference: &DiagramReference, module_name: &str, dictionary: &DataDictionary, depth: usize) -> Result<String> {
+        let is_root = depth == 0;
+        // Les activités sont générées dans l'ordre où `run_process` les appelle
+        let order = execution_order(diagram)?;
+        let names = DataNames::new(diagram, &order, dictionary);
+        let numbers = box_numbers(diagram);
+
+        let mut arrows: Vec<&Arrow> = diagram.arrows.values().collect();
+        arrows.sort_by(|a, b| a.label.cmp(&b.label).then(a.id.cmp(&b.id)));
+
+        // Entrées/sorties du processus: les flèches frontières, dans l'ordre des codes ICOM
+        let mut boundary: Vec<&Arrow> = diagram.boundary_arrows().collect();
+        boundary.sort_by_key(|arrow| arrow.icom().map(|code| (code.arrow_type.icom_rank(), code.index)));
+        let params: Vec<ParamContext> = boundary.iter().filter(|a| a.source.icom().is_some()).map(|a| names.param(a)).collect();
+        let ref_params: HashSet<String> = params.iter().filter(|p| p.by_ref).map(|p| p.name.clone()).collect();
+        // Sorties du processus: flèches qui arrivent au cadre, directement ou par une jonction
+        let mut outgoing: Vec<&Arrow> = arrows.iter().copied().filter(|a| diagram.delivered(a).target.icom().is_some()).collect();
+        outgoing.sort_by_key(|a| diagram.delivered(a).target.icom().map(|code| code.index));
+        let results: Vec<ParamContext> = outgoing.iter().map(|a| names.param(a)).collect();
+        let result_types: Vec<&str> = results.iter().map(|p| p.ty.as_str()).collect();
+        let mut functions_context = Vec::new();
+        let mut step_calls = Vec::new();
+        for node_id in &order {
+            let Some(node) = diagram.get_node(*node_id) else { continue };
+            let mut inputs = Vec::new();
+            let mut outputs = Vec::new();
+            let mut controls = Vec::new();
+            let mut mechanisms = Vec::new();
+
+            for arrow in &arrows {
+                let delivered = diagram.delivered(arrow);
+                if delivered.target.is_node(node.id) {
+                    let param = names.param(arrow);
+                    // Une même donnée arrivant par plusieurs embranchements n'est reçue qu'une fois
+                    if inputs.iter().chain(&controls).chain(&mechanisms).any(|p: &ParamContext| p.name == param.name) { continue; }
+                    match delivered.consumed_role() {
+                        ArrowType::Control => controls.push(param),
+                        ArrowType::Mechanism => mechanisms.push(param),
+                        _ => inputs.push(param),
+                    }
+                } else if arrow.source.is_node(node.id) {
+                    outputs.push(names.param(arrow));
+                }
+            }
+
+            // Arguments dans l'ordre des paramètres (prêts et clones décidés plus bas,
+            // une fois connus tous les usages de chaque donnée)
+            let args: Vec<(String, bool)> = inputs.iter().chain(&controls).chain(&mechanisms)
+                .map(|p| (p.name.clone(), p.by_ref))
+                .collect();
+            let ident = names.function(node.id);
+            let number = numbers.get(&node.id).copied().unwrap_or_default();
+            let call = match applicable_algorithm(diagram, dictionary, node) {
+                Some(algorithm) => {
+                    let inputs: Vec<&str> = inputs.iter().map(|p| p.name.as_str()).collect();
+                    let call = format!("{}{}::{}({})", "super::".repeat(depth), LIBRARY_MODULE, algorithm.name, inputs.join(", "));
+                    // Le résultat est transmis sur chacune des sorties
+                    match outputs.len() {
+                        1 => call,
+                        n => format!("let result = {};\n    ({}result)", call, "result.clone(), ".repeat(n - 1)),
+                    }
+                }
+                None => String::new(),
+            };
+            let output_names: Vec<&str> = outputs.iter().map(|p| p.name.as_str()).collect();
+            let output_types: Vec<&str> = outputs.iter().map(|p| p.ty.as_str()).collect();
+            step_calls.push((ident.clone(), tuple_of(&output_names, ""), args));
+
+            functions_context.push(FunctionContext {
+                name: &node.name, // Référence ok ici car ModuleContext a un lifetime
+                id: node.id.to_string(),
+                ident,
+                number,
+                reference: reference.box_reference(number),
+                return_type: tuple_of(&output_types, ""),
+                inputs,
+                outputs,
+                controls,
+                mechanisms,
+                call,
+            });
+        }
+
+        // Une donnée consommée plusieurs fois (embranchements) est clonée, sauf à son dernier
+        // usage. Les ressources sont prêtées; celles reçues par `run_process` sont déjà des références.
+        let results: Vec<(String, bool)> = results.iter().map(|p| (p.name.clone(), false)).collect();
+        let mut uses: Vec<&str> = step_calls.iter().flat_map(|(_, _, args)| args).chain(&results).map(|(name, _)| name.as_str()).collect();
+        let mut arguments = |args: &[(String, bool)]| -> Vec<String> {
+            args.iter().map(|(name, by_ref)| {
+                uses.remove(0);
+                let used_later = uses.contains(&name.as_str());
+                match (by_ref, ref_params.contains(name)) {
+                    (_, true) => name.clone(),
+                    (true, false) => format!("&{}", name),
+                    (false, false) if used_later => format!("{}.clone()", name),
+                    (false, false) => name.clone(),
+                }
+            }).collect()
+        };
+        let steps: Vec<StepContext> = step_calls.iter()
+            .map(|(function, bindings, args)| StepContext { function: function.clone(), bindings: bindings.clone(), args: arguments(args).join(", ") })
+            .collect();
+        let result_values = arguments(&results);
+        let result_values: Vec<&str> = result_values.iter().map(String::as_str).collect();
+        let pipeline = PipelineContext {
+            params,
+            steps,
+            return_type: tuple_of(&result_types, ""),
+            result: tuple_of(&result_values, ""),
+        };
+
+        // Un sous-module par activité décomposée, généré récursivement, du nom de sa fonction
+        // (unique, et distinct de la bibliothèque d'algorithmes)
+        let mut submodules = Vec::new();
+        for (node, child) in diagram.decomposed_nodes() {
+            let name = names.function(node.id);
+            submodules.push(SubmoduleContext {
+                code: self.render_module(child, &reference.child(diagram, node.id, child), &name, dictionary, depth + 1)?,
+                name,
+                parent_name: node.name.clone(),
+            });
+        }
+
+        let placeholders = PLACEHOLDER_TYPES.into_iter()
+            .filter(|placeholder| arrows.iter().any(|arrow| names.param(arrow).ty == *placeholder))
+            .collect();
+        let context = ModuleContext {
+            module_name,
+            metadata: MetadataContext::new(&diagram.metadata, reference),
+            data_types: if is_root { data_type_contexts(dictionary) } else { Vec::new() },
+            import_parent: !is_root && uses_dictionary(diagram, dictionary),
+            algorithm_library: if is_root && uses_algorithms(diagram, dictionary) { LIBRARY_SOURCE } else { "" },
+            placeholders,
+            functions: functions_context,
+            pipeline,
+            submodules,
+        };
+
+        let tera_context = Context::from_serialize(context)?;
+        let rendered = self.tera.render("rust_module.tera", &tera_context)?; // Utilise le bon template
+        Ok(rendered)
+    }
+
+     pub fn generate_markdown_doc(&self, diagram: &SadtDiagram) -> Result<String> {
+        let mut diagrams = Vec::new();
+        let reference = DiagramReference::root(diagram);
+        collect_markdown_diagrams(diagram, &reference, "Diagramme principal".to_string(), None, 0, &mut diagrams);
+
+        let context = MarkdownDocContext {
+            metadata: MetadataContext::new(&diagram.metadata, &reference),
+            data_types: data_type_contexts(&diagram.data_dictionary),
+            diagrams,
+        };
+        let tera_context = Context::from_serialize(context)?;
+        let rendered = self.tera.render("markdown_doc.tera", &tera_context)?;
+        Ok(rendered)
+     }
+}
+
+// Parcourt la hiérarchie en profondeur pour la documentation
+fn collect_markdown_diagrams<'a>(
+    diagram: &'a SadtDiagram,
+    reference: &DiagramReference,
+    title: String,
+    parent_name: Option<String>,
+    depth: usize,
+    out: &mut Vec<MarkdownDiagramContext<'a>>,
+) {
+    // Activités dans l'ordre de leurs numéros de boîte
+    let numbers = box_numbers(diagram);
+    let nodes = numbered_order(diagram).into_iter().filter_map(|id| diagram.get_node(id)).map(|node| {
+        MarkdownNodeContext {
+            id: node.id.to_string(),
+            number: numbers[&node.id],
+            reference: reference.box_reference(numbers[&node.id]),
+            name: node.name.clone(),
+            description: node.description.clone(),
+            x: node.rect.min.x,
+            y: node.rect.min.y,
+            width: node.rect.width(),
+            height: node.rect.height(),
+        }
+    }).collect();
+    out.push(MarkdownDiagramContext { title, metadata: MetadataContext::new(&diagram.metadata, reference), parent_name, depth, nodes });
+
+    let mut children = diagram.decomposed_nodes();
+    children.sort_by_key(|(node, _)| numbers[&node.id]);
+    for (node, child) in children {
+        let child_reference = reference.child(diagram, node.id, child);
+        collect_markdown_diagrams(child, &child_reference, format!("Décomposition de \"{}\"", node.name), Some(node.name.clone()), depth + 1, out);
+    }
+}
+
+// Identifiants Rust des activités et des données (une variable par flèche, partagée
+// par ses embranchements), sans collision entre eux ni avec `run_process`
+struct DataNames<'a> {
+    functions: HashMap<NodeId, String>,
+    data: HashMap<ArrowId, String>,
+    algorithm_types: HashMap<ArrowId, &'static str>, // Types imposés par les algorithmes
+    diagram: &'a SadtDiagram,
+    dictionary: &'a DataDictionary,
+}
+
+impl<'a> DataNames<'a> {
+    fn new(diagram: &'a SadtDiagram, order: &[NodeId], dictionary: &'a DataDictionary) -> Self {
+        let mut used = HashSet::from(["run_process".to_string(), LIBRARY_MODULE.to_string()]);
+        let mut functions = HashMap::new();
+        for node_id in order {
+            if let Some(node) = diagram.get_node(*node_id) {
+                functions.insert(*node_id, unique_ident(&to_rust_ident(&node.name), &mut used));
+            }
+        }
+
+        // Nommage dans l'ordre d'apparition: entrées du processus, puis sorties de chaque activité
+        let mut arrows: Vec<&Arrow> = diagram.arrows.values().filter(|arrow| arrow.source.junction().is_none()).collect();
+        arrows.sort_by(|a, b| a.label.cmp(&b.label).then(a.id.cmp(&b.id)));
+        let producer_rank = |arrow: &Arrow| arrow.source.node_id().and_then(|id| order.iter().position(|n| *n == id));
+        arrows.sort_by_key(|arrow| producer_rank(arrow).map_or(0, |rank| rank + 1));
+        let mut data = HashMap::new();
+        for arrow in arrows {
+            let base = arrow.label.as_deref().map(to_rust_ident).unwrap_or_else(|| "data".to_string());
+            data.insert(arrow.id, unique_ident(&base, &mut used));
+        }
+        // Flèches sans type du dictionnaire reliées à une activité avec algorithme
+        let mut algorithm_types = HashMap::new();
+        for node in diagram.nodes.values() {
+            let Some(algorithm) = applicable_algorithm(diagram, dictionary, node) else { continue };
+            for (arrow, param) in diagram.node_inputs(node.id).into_iter().zip(algorithm.params) {
+                algorithm_types.insert(diagram.origin(arrow).id, param.ty);
+            }
+            for arrow in diagram.node_outputs(node.id) {
+                algorithm_types.insert(arrow.id, algorithm.output);
+            }
+        }
+        DataNames { functions, data, algorithm_types, diagram, dictionary }
+    }
+
+    fn function(&self, node_id: NodeId) -> String {
+        self.functions.get(&node_id).cloned().unwrap_or_default()
+    }
+
+    // Donnée livrée par une flèche: variable et type de la flèche d'origine (un embranchement
+    // transporte la donnée de son tronc), rôle de la flèche d'arrivée (pour une jonction, le faisceau)
+    fn param(&self, arrow: &Arrow) -> ParamContext {
+        let origin = self.diagram.origin(arrow);
+        let delivered = self.diagram.delivered(arrow);
+        ParamContext {
+            name: self.data.get(&origin.id).cloned().unwrap_or_default(),
+            label: arrow.label.clone().or_else(|| origin.label.clone()).unwrap_or_else(|| "data".to_string()),
+            ty: dictionary_type(origin, self.dictionary)
+                .or_else(|| self.algorithm_types.get(&origin.id).copied())
+                .unwrap_or_else(|| placeholder_type(self.diagram.delivered(origin)))
+                .to_string(),
+            by_ref: delivered.consumed_role() == ArrowType::Mechanism && delivered.target.node_id().is_some(),
+        }
+    }
+}
+
+fn unique_ident(base: &str, used: &mut HashSet<String>) -> String {
+    let mut ident = base.to_string();
+    let mut suffix = 2;
+    while !used.insert(ident.clone()) {
+        ident = format!("{}_{}", base, suffix);
+        suffix += 1;
+    }
+    ident
+}
+
+// Type placeholder d'une flèche, identique chez l'activité qui la produit et celle qui la reçoit
+fn placeholder_type(arrow: &Arrow) -> &'static str {
+    match arrow.consumed_role() {
+        ArrowType::Input => "InputData",
+        ArrowType::Output => "OutputData",
+        ArrowType::Control => "ControlParam",
+        ArrowType::Mechanism => "MechanismResource",
+    }
+}
+
+fn uses_algorithms(diagram: &SadtDiagram, dictionary: &DataDictionary) -> bool {
+    diagram.nodes.values().any(|node| applicable_algorithm(diagram, dictionary, node).is_some())
+        || diagram.decomposed_nodes().iter().any(|(_, child)| uses_algorithms(child, dictionary))
+}
+
+// Un sous-module importe les types du dictionnaire s'il en utilise, lui ou l'un de ses
+// sous-modules (qui les reçoivent par son import)
+fn uses_dictionary(diagram: &SadtDiagram, dictionary: &DataDictionary) -> bool {
+    diagram.arrows.values().any(|arrow| dictionary_type(arrow, dictionary).is_some())
+        || diagram.decomposed_nodes().iter().any(|(_, child)| uses_dictionary(child, dictionary))
+}
+
+// Type du dictionnaire référencé par une flèche (None si absent ou inconnu du dictionnaire)
+fn dictionary_type<'a>(arrow: &'a Arrow, dictionary: &DataDictionary) -> Option<&'a str> {
+    arrow.data_type.as_deref().filter(|name| dictionary.contains(name))
+}
+
+fn data_type_contexts(dictionary: &DataDictionary) -> Vec<DataTypeContext<'_>> {
+    dictionary.types.iter().map(|data_type| {
+        let (kind, fields, variants, path) = match &data_type.kind {
+            DataTypeKind::Struct(fields) => ("struct", fields.as_slice(), &[][..], ""),
+            DataTypeKind::Enum(variants) => ("enum", &[][..], variants.as_slice(), ""),
+            DataTypeKind::Alias(path) => ("alias", &[][..], &[][..], path.as_str()),
+        };
+        let clone = dictionary.is_clone(&data_type.name);
+        DataTypeContext { name: &data_type.name, description: &data_type.description, kind, fields, variants, path, clone }
+    }).collect()
+}
+
+// Type ou motif tuple: `vide` si aucun élément, l'élément seul s'il n'y en a qu'un
+fn tuple_of(items: &[&str], empty: &str) -> String {
+    match items {
+        [] => empty.to_string(),
+        [single] => single.to_string(),
+        _ => format!("({})", items.join(", ")),
+    }
+}
+
+// Convertit un nom d'activité en identifiant Rust valide (snake_case ASCII)
+pub fn to_rust_ident(name: &str) -> String {
+    let mut ident = String::new();
+    for c in name.chars().map(fold_accent) {
+        if c.is_ascii_alphanumeric() {
+            ident.push(c.to_ascii_lowercase());
+        } else if !ident.ends_with('_') {
+            ident.push('_');
+        }
+    }
+    let ident = ident.trim_matches('_').to_string();
+    match ident.chars().next() {
+        None => "activite".to_string(),
+        Some(c) if c.is_ascii_digit() => format!("a_{}", ident),
+        Some(_) if is_rust_keyword(&ident) => format!("{}_", ident),
+        Some(_) => ident,
+    }
+}
+
+// Retire les accents courants du français ("Générer" -> "Generer")
+fn fold_accent(c: char) -> char {
+    match c {
+        'à' | 'â' | 'ä' | 'À' | 'Â' | 'Ä' => 'a',
+        'é' | 'è' | 'ê' | 'ë' | 'É' | 'È' | 'Ê' | 'Ë' => 'e',
+        'î' | 'ï' | 'Î' | 'Ï' => 'i',
+        'ô' | 'ö' | 'Ô' | 'Ö' => 'o',
+        'ù' | 'û' | 'ü' | 'Ù' | 'Û' | 'Ü' => 'u',
+        'ç' | 'Ç' => 'c',
+        _ => c,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::reverse::diagram_from_rust;
+
+    fn generator() -> CodeGenerator {
+        CodeGenerator::from_dir(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../templates")).unwrap()
+    }
+
+    fn decompose(diagram: &mut SadtDiagram, node_id: NodeId, inner: &str) {
+        let mut child = SadtDiagram::new();
+        child.add_node(inner.to_string(), Point::ZERO);
+        diagram.get_node_mut(node_id).unwrap().child_diagram = Some(child);
+    }
+
+    fn module_names(code: &str) -> Vec<String> {
+        syn::parse_file(code).unwrap().items.iter()
+            .filter_map(|item| match item { syn::Item::Mod(module) => Some(module.ident.to_string()), _ => None })
+            .collect()
+    }
+
+    // Homonymes et activité nommée comme la bibliothèque: un module distinct chacun
+    #[test]
+    fn submodule_names_are_unique() {
+        let mut diagram = SadtDiagram::new();
+        for (index, name) in ["Traiter", "Traiter", "Algorithms"].into_iter().enumerate() {
+            let node_id = diagram.add_node(name.to_string(), Point::new(index as f32 * 200.0, 0.0));
+            decompose(&mut diagram, node_id, &format!("Étape {}", index));
+        }
+        let code = generator().generate_rust_module(&diagram, "process").unwrap();
+        let mut names = module_names(&code);
+        names.sort();
+        assert_eq!(names, ["algorithms_2", "traiter", "traiter_2"]);
+
+        // Chaque sous-module redevient le sous-diagramme de son activité
+        let imported = diagram_from_rust(&code).unwrap();
+        for node in diagram.nodes.values() {
+            let child = imported.nodes[&node.id].child_diagram.as_ref().unwrap();
+            let inner = node.child_diagram.as_ref().unwrap().nodes.values().next().unwrap();
+            assert!(child.nodes.contains_key(&inner.id));
+        }
+    }
+
+    // Seul le sous-module dont une flèche porte un type du dictionnaire l'importe
+    #[test]
+    fn submodules_import_dictionary_only_when_used() {
+        use crate::data_dictionary::DataType;
+        use crate::sadt_elements::{ArrowType, ConnectionPoint, Side};
+        let mut diagram = SadtDiagram::new();
+        diagram.data_dictionary.types.push(DataType { name: "Commande".to_string(), description: String::new(), kind: DataTypeKind::Alias("String".to_string()) });
+        let typed = diagram.add_node("Saisir".to_string(), Point::ZERO);
+        let untyped = diagram.add_node("Livrer".to_string(), Point::new(200.0, 0.0));
+        decompose(&mut diagram, untyped, "Emballer");
+        let mut child = SadtDiagram::new();
+        let first = child.add_node("Lire".to_string(), Point::ZERO);
+        let second = child.add_node("Vérifier".to_string(), Point::new(200.0, 0.0));
+        let arrow = child.add_arrow(ConnectionPoint::new(first, Side::Right), ConnectionPoint::new(second, Side::Left), ArrowType::Output, None).unwrap();
+        child.arrows.get_mut(&arrow).unwrap().data_type = Some("Commande".to_string());
+        diagram.get_node_mut(typed).unwrap().child_diagram = Some(child);
+
+        let code = generator().generate_rust_module(&diagram, "process").unwrap();
+        let imports: Vec<String> = syn::parse_file(&code).unwrap().items.iter()
+            .filter_map(|item| match item { syn::Item::Mod(module) => Some(module), _ => None })
+            .filter(|module| module.content.iter().flat_map(|(_, items)| items).any(|item| matches!(item, syn::Item::Use(_))))
+            .map(|module| module.ident.to_string())
+            .collect();
+        assert_eq!(imports, ["saisir"]);
+    }
+
+    fn baseline() -> SadtDiagram {
+        crate::persistence::load_diagram(&Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/simple_process_v0.ron")).unwrap()
+    }
+
+    // `generate-code --update`: le corps écrit à la main survit, le reste suit le diagramme
+    #[test]
+    fn update_keeps_hand_written_bodies() {
+        let mut diagram = baseline();
+        let code = generator().generate_rust_module(&diagram, "simple_process").unwrap();
+        let todo = "todo!(\"Return actual output(s) for Traiter Données\")";
+        assert!(code.contains(todo));
+        let edited = code.replace(todo, "donnees_brutes");
+
+        diagram.metadata.author = "Équipe qualité".to_string();
+        let (updated, report) = generator().regenerate_rust_module(&diagram, "simple_process", &edited).unwrap();
+        assert!(updated.contains("//! Author: Équipe qualité"));
+        assert!(updated.contains("donnees_brutes\n") && !updated.contains(todo));
+        assert_eq!(report.preserved, ["traiter_donnees"]);
+        assert!(report.conflicts.is_empty());
+        syn::parse_file(&updated).unwrap();
+    }
+}
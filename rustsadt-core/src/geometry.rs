@@ -0,0 +1,241 @@
+// rustsadt-core/src/geometry.rs
+// Types géométriques du modèle, en coordonnées monde (indépendants de toute bibliothèque graphique).
+// Le format sérialisé est identique à celui des types egui utilisés auparavant.
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub x: f32,
+    pub y: f32,
+}
+
+// Rectangle aligné sur les axes, `min` en haut à gauche
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+pub const fn point(x: f32, y: f32) -> Point {
+    Point { x, y }
+}
+
+pub const fn vec2(x: f32, y: f32) -> Vec2 {
+    Vec2 { x, y }
+}
+
+impl Point {
+    pub const ZERO: Point = point(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        point(x, y)
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        vec2(self.x, self.y)
+    }
+
+    pub fn distance_sq(self, other: Point) -> f32 {
+        (self - other).length_sq()
+    }
+
+    pub fn distance(self, other: Point) -> f32 {
+        self.distance_sq(other).sqrt()
+    }
+
+    pub fn lerp(self, other: Point, t: f32) -> Point {
+        self + (other - self) * t
+    }
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = vec2(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        vec2(x, y)
+    }
+
+    pub fn length_sq(self) -> f32 {
+        self.x * self.x + self.y * self.y
+    }
+
+    pub fn length(self) -> f32 {
+        self.length_sq().sqrt()
+    }
+
+    // Vecteur unitaire (nul si le vecteur est nul)
+    pub fn normalized(self) -> Vec2 {
+        let length = self.length();
+        if length == 0.0 { self } else { self / length }
+    }
+}
+
+impl Rect {
+    // Rectangle vide, neutre pour `union`
+    pub const NOTHING: Rect = Rect { min: point(f32::INFINITY, f32::INFINITY), max: point(f32::NEG_INFINITY, f32::NEG_INFINITY) };
+
+    pub const fn from_min_max(min: Point, max: Point) -> Self {
+        Rect { min, max }
+    }
+
+    pub fn from_min_size(min: Point, size: Vec2) -> Self {
+        Rect { min, max: min + size }
+    }
+
+    pub fn from_center_size(center: Point, size: Vec2) -> Self {
+        Rect { min: center - size / 2.0, max: center + size / 2.0 }
+    }
+
+    pub fn from_two_pos(a: Point, b: Point) -> Self {
+        Rect { min: point(a.x.min(b.x), a.y.min(b.y)), max: point(a.x.max(b.x), a.y.max(b.y)) }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max.x - self.min.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max.y - self.min.y
+    }
+
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn center(&self) -> Point {
+        self.min.lerp(self.max, 0.5)
+    }
+
+    pub fn left(&self) -> f32 {
+        self.min.x
+    }
+
+    pub fn right(&self) -> f32 {
+        self.max.x
+    }
+
+    pub fn top(&self) -> f32 {
+        self.min.y
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.max.y
+    }
+
+    // Vrai pour un rectangle vide (comme `Rect::NOTHING`)
+    pub fn is_negative(&self) -> bool {
+        self.max.x < self.min.x || self.max.y < self.min.y
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x && self.min.y <= p.y && p.y <= self.max.y
+    }
+
+    pub fn intersects(&self, other: Rect) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    pub fn union(self, other: Rect) -> Rect {
+        Rect {
+            min: point(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            max: point(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        }
+    }
+
+    pub fn extend_with(&mut self, p: Point) {
+        *self = self.union(Rect::from_min_max(p, p));
+    }
+
+    pub fn expand(self, margin: f32) -> Rect {
+        Rect { min: self.min - vec2(margin, margin), max: self.max + vec2(margin, margin) }
+    }
+
+    pub fn shrink(self, margin: f32) -> Rect {
+        self.expand(-margin)
+    }
+
+    pub fn translate(self, delta: Vec2) -> Rect {
+        Rect { min: self.min + delta, max: self.max + delta }
+    }
+}
+
+impl Add<Vec2> for Point {
+    type Output = Point;
+    fn add(self, rhs: Vec2) -> Point {
+        point(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vec2> for Point {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vec2> for Point {
+    type Output = Point;
+    fn sub(self, rhs: Vec2) -> Point {
+        point(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Vec2;
+    fn sub(self, rhs: Point) -> Vec2 {
+        vec2(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+    fn add(self, rhs: Vec2) -> Vec2 {
+        vec2(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        vec2(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Vec2) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+    fn neg(self) -> Vec2 {
+        vec2(-self.x, -self.y)
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+    fn mul(self, rhs: f32) -> Vec2 {
+        vec2(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Div<f32> for Vec2 {
+    type Output = Vec2;
+    fn div(self, rhs: f32) -> Vec2 {
+        vec2(self.x / rhs, self.y / rhs)
+    }
+}
@@ -0,0 +1,264 @@
+// rustsadt-core/src/layout.rs
+// Disposition automatique d'un diagramme en escalier IDEF0: une activité par marche,
+// de haut à gauche en bas à droite, dans l'ordre des dépendances (les numéros de boîte,
+// fixés à la création, n'y jouent aucun rôle). Les sorties
+// (côté droit) descendent ainsi vers les entrées et contrôles des activités suivantes.
+// Les écarts entre marches laissent un couloir par flèche qui y passe.
+// Aussi: alignement et répartition d'un groupe de boîtes sélectionnées.
+use crate::geometry::{point, vec2, Point, Rect};
+use crate::pipeline::dependencies;
+use crate::routing::ROUTE_CLEARANCE;
+use crate::sadt_elements::{ArrowEnd, NodeId, Side};
+use crate::sadt_model::SadtDiagram;
+use std::collections::HashSet;
+
+const LAYOUT_ORIGIN: Point = point(100.0, 100.0); // Coin de la première activité d'un diagramme vide
+const STEP_GAP_X: f32 = 40.0; // Écart horizontal minimal entre deux marches
+const STEP_GAP_Y: f32 = 20.0; // Écart vertical minimal entre deux marches
+const MAX_SWAP_PASSES: usize = 20;
+
+// Dispose les activités du diagramme (sans toucher aux sous-diagrammes). Les tracés
+// imposés sont abandonnés et les flèches de chaque côté réordonnées selon la position
+// de leur autre extrémité, sauf sur les boîtes décomposées: leurs emplacements fixent
+// les codes ICOM de leur sous-diagramme.
+pub fn auto_layout(diagram: &mut SadtDiagram) {
+    if diagram.nodes.is_empty() { return; }
+    let order = staircase_order(diagram);
+
+    // Le diagramme garde son coin supérieur gauche
+    let content = diagram.nodes.values().fold(Rect::NOTHING, |acc, node| acc.union(node.rect));
+    let mut min = if content.is_negative() { LAYOUT_ORIGIN } else { content.min };
+    let mut previous: Option<(NodeId, Rect)> = None;
+    for &node_id in &order {
+        if let Some((previous_id, rect)) = previous {
+            let lanes = |node_id, side| diagram.side_arrows(node_id, side).len() as f32 * ROUTE_CLEARANCE;
+            min.x = rect.right() + STEP_GAP_X + lanes(previous_id, Side::Right) + lanes(node_id, Side::Top);
+            min.y = rect.bottom() + STEP_GAP_Y + lanes(previous_id, Side::Bottom) + lanes(node_id, Side::Left);
+        }
+        let Some(node) = diagram.get_node_mut(node_id) else { continue };
+        node.rect = Rect::from_min_size(min, node.rect.size());
+        previous = Some((node_id, node.rect));
+    }
+
+    for arrow in diagram.arrows.values_mut() {
+        arrow.waypoints.clear();
+    }
+    reorder_slots(diagram);
+}
+
+// Dispose le diagramme et tous ses sous-diagrammes
+pub fn auto_layout_hierarchy(diagram: &mut SadtDiagram) {
+    auto_layout(diagram);
+    for node in diagram.nodes.values_mut() {
+        if let Some(child) = node.child_diagram.as_mut() {
+            auto_layout_hierarchy(child);
+        }
+    }
+}
+
+// Ordre des marches: tri topologique qui place d'abord la suite de l'activité la plus
+// récente (les chaînes restent groupées), puis échanges de voisines réduisant les croisements.
+// Un cycle (flèche de retour) est rompu à l'activité qui a le moins de prédécesseurs à placer.
+pub(crate) fn staircase_order(diagram: &SadtDiagram) -> Vec<NodeId> {
+    let edges: Vec<(NodeId, NodeId)> = dependencies(diagram).into_iter().filter(|(a, b)| a != b).collect();
+    let mut nodes: Vec<_> = diagram.nodes.values().collect();
+    // À défaut de dépendance, l'ordre actuel (de haut à gauche en bas à droite) est conservé
+    nodes.sort_by(|a, b| (a.rect.left() + a.rect.top()).total_cmp(&(b.rect.left() + b.rect.top())).then(a.id.cmp(&b.id)));
+
+    let mut order: Vec<NodeId> = Vec::with_capacity(nodes.len());
+    let mut remaining: Vec<NodeId> = nodes.iter().map(|node| node.id).collect();
+    while !remaining.is_empty() {
+        let pending = |id: &NodeId| edges.iter().filter(|(source, target)| target == id && !order.contains(source)).count();
+        let latest_predecessor = |id: &NodeId| edges.iter()
+            .filter(|(_, target)| target == id)
+            .filter_map(|(source, _)| order.iter().position(|placed| placed == source))
+            .max();
+        let next = remaining.iter().enumerate()
+            .min_by_key(|(index, id)| (pending(id), std::cmp::Reverse(latest_predecessor(id)), *index))
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+        order.push(remaining.remove(next));
+    }
+
+    reduce_crossings(&mut order, &edges);
+    order
+}
+
+// Échange deux marches voisines non reliées tant que cela réduit les croisements
+fn reduce_crossings(order: &mut [NodeId], edges: &[(NodeId, NodeId)]) {
+    let linked: HashSet<(NodeId, NodeId)> = edges.iter().flat_map(|&(a, b)| [(a, b), (b, a)]).collect();
+    let mut best = crossings(order, edges);
+    for _ in 0..MAX_SWAP_PASSES {
+        let mut improved = false;
+        for index in 0..order.len().saturating_sub(1) {
+            if linked.contains(&(order[index], order[index + 1])) { continue; }
+            order.swap(index, index + 1);
+            let count = crossings(order, edges);
+            if count < best {
+                best = count;
+                improved = true;
+            } else {
+                order.swap(index, index + 1);
+            }
+        }
+        if !improved { break; }
+    }
+}
+
+// Croisements entre flèches dans l'escalier: deux liaisons se croisent quand leurs
+// intervalles de marches s'entrelacent
+fn crossings(order: &[NodeId], edges: &[(NodeId, NodeId)]) -> usize {
+    let rank = |id: &NodeId| order.iter().position(|placed| placed == id).unwrap_or(0);
+    let spans: Vec<(usize, usize)> = edges.iter()
+        .map(|(source, target)| (rank(source), rank(target)))
+        .map(|(a, b)| (a.min(b), a.max(b)))
+        .collect();
+    let mut count = 0;
+    for (index, &(lo1, hi1)) in spans.iter().enumerate() {
+        for &(lo2, hi2) in &spans[index + 1..] {
+            if (lo1 < lo2 && lo2 < hi1 && hi1 < hi2) || (lo2 < lo1 && lo1 < hi2 && hi2 < hi1) {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+// Renumérote les emplacements des boîtes non décomposées dans l'ordre des positions
+// de l'autre extrémité (voir `SadtDiagram::side_arrows`)
+fn reorder_slots(diagram: &mut SadtDiagram) {
+    let free: HashSet<NodeId> = diagram.nodes.values().filter(|node| !node.is_decomposed()).map(|node| node.id).collect();
+    for arrow in diagram.arrows.values_mut() {
+        for end in [&mut arrow.source, &mut arrow.target] {
+            if let ArrowEnd::Node(cp) = end {
+                if free.contains(&cp.node_id) { cp.slot = 0; }
+            }
+        }
+    }
+    for &node_id in &free {
+        for side in [Side::Left, Side::Top, Side::Right, Side::Bottom] {
+            for (slot, arrow_id) in diagram.side_arrows(node_id, side).into_iter().enumerate() {
+                let Some(arrow) = diagram.arrows.get_mut(&arrow_id) else { continue };
+                for end in [&mut arrow.source, &mut arrow.target] {
+                    if let ArrowEnd::Node(cp) = end {
+                        if cp.node_id == node_id && cp.side == side { cp.slot = slot as u32; }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Alignement d'un groupe de boîtes sur un bord ou un axe de leur rectangle englobant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    CenterX, // Centres sur une même verticale
+    Right,
+    Top,
+    CenterY, // Centres sur une même horizontale
+    Bottom,
+}
+
+pub fn align_nodes(diagram: &mut SadtDiagram, ids: &[NodeId], alignment: Alignment) {
+    let bounds = ids.iter()
+        .filter_map(|id| diagram.get_node(*id))
+        .fold(Rect::NOTHING, |acc, node| acc.union(node.rect));
+    if bounds.is_negative() { return; }
+    for id in ids {
+        let Some(node) = diagram.get_node_mut(*id) else { continue };
+        let rect = node.rect;
+        let delta = match alignment {
+            Alignment::Left => vec2(bounds.left() - rect.left(), 0.0),
+            Alignment::CenterX => vec2(bounds.center().x - rect.center().x, 0.0),
+            Alignment::Right => vec2(bounds.right() - rect.right(), 0.0),
+            Alignment::Top => vec2(0.0, bounds.top() - rect.top()),
+            Alignment::CenterY => vec2(0.0, bounds.center().y - rect.center().y),
+            Alignment::Bottom => vec2(0.0, bounds.bottom() - rect.bottom()),
+        };
+        node.rect = rect.translate(delta);
+    }
+}
+
+// Répartit les boîtes à intervalles égaux entre les deux extrêmes (qui ne bougent pas)
+pub fn distribute_nodes(diagram: &mut SadtDiagram, ids: &[NodeId], horizontal: bool) {
+    // Début et étendue d'une boîte le long de l'axe de répartition
+    let span = |rect: &Rect| if horizontal { (rect.left(), rect.width()) } else { (rect.top(), rect.height()) };
+    let mut rects: Vec<(NodeId, Rect)> = ids.iter()
+        .filter_map(|id| diagram.get_node(*id).map(|node| (node.id, node.rect)))
+        .collect();
+    if rects.len() < 3 { return; }
+    rects.sort_by(|a, b| span(&a.1).0.total_cmp(&span(&b.1).0));
+    let first = span(&rects[0].1).0;
+    let last = rects.iter().map(|(_, rect)| span(rect).0 + span(rect).1).fold(first, f32::max);
+    let occupied: f32 = rects.iter().map(|(_, rect)| span(rect).1).sum();
+    let gap = (last - first - occupied) / (rects.len() - 1) as f32;
+
+    let mut position = first;
+    for (id, rect) in rects {
+        let (current, extent) = span(&rect);
+        let delta = if horizontal { vec2(position - current, 0.0) } else { vec2(0.0, position - current) };
+        if let Some(node) = diagram.get_node_mut(id) { node.rect = rect.translate(delta); }
+        position += extent + gap;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sadt_elements::{ArrowType, ConnectionPoint};
+    use uuid::Uuid;
+
+    fn link(diagram: &mut SadtDiagram, source: NodeId, target: NodeId) {
+        diagram.add_arrow(ConnectionPoint::new(source, Side::Right), ConnectionPoint::new(target, Side::Left), ArrowType::Output, None).unwrap();
+    }
+
+    // Les dépendances priment sur la position; la suite de la dernière activité placée vient d'abord
+    #[test]
+    fn staircase_follows_dependencies() {
+        let mut diagram = SadtDiagram::new();
+        let report = diagram.add_node("Rapport".to_string(), point(0.0, 0.0));
+        let read = diagram.add_node("Lire".to_string(), point(400.0, 400.0));
+        let check = diagram.add_node("Vérifier".to_string(), point(200.0, 200.0));
+        let sort = diagram.add_node("Trier".to_string(), point(600.0, 600.0));
+        link(&mut diagram, read, check);
+        link(&mut diagram, check, report);
+        link(&mut diagram, read, sort);
+        let order = staircase_order(&diagram);
+        let rank = |id| order.iter().position(|placed| *placed == id).unwrap();
+        assert!(rank(read) < rank(check) && rank(check) < rank(report) && rank(read) < rank(sort));
+        assert_eq!(rank(report), rank(check) + 1);
+    }
+
+    // Deux liaisons entrelacées se démêlent en échangeant les marches non reliées
+    #[test]
+    fn swaps_reduce_crossings() {
+        let ids: Vec<NodeId> = (0..4).map(|_| Uuid::new_v4()).collect();
+        let edges = [(ids[0], ids[2]), (ids[1], ids[3])];
+        let mut order = ids.clone();
+        assert_eq!(crossings(&order, &edges), 1);
+        reduce_crossings(&mut order, &edges);
+        assert_eq!(crossings(&order, &edges), 0);
+    }
+
+    // Chaque boîte est une marche en bas à droite de celle qui l'alimente, quel que
+    // soit l'ordre de création (donc des numéros de boîte)
+    #[test]
+    fn layout_places_boxes_in_steps() {
+        for consumer_first in [false, true] {
+            let mut diagram = SadtDiagram::new();
+            let (producer, consumer) = if consumer_first {
+                let consumer = diagram.add_node("Rapport".to_string(), point(0.0, 0.0));
+                (diagram.add_node("Lire".to_string(), point(0.0, 200.0)), consumer)
+            } else {
+                let producer = diagram.add_node("Lire".to_string(), point(500.0, 0.0));
+                (producer, diagram.add_node("Rapport".to_string(), point(0.0, 500.0)))
+            };
+            link(&mut diagram, producer, consumer);
+            auto_layout(&mut diagram);
+            let (a, b) = (diagram.nodes[&producer].rect, diagram.nodes[&consumer].rect);
+            assert!(b.left() > a.right() && b.top() > a.bottom(), "{:?} {:?}", a, b);
+            assert!(!a.intersects(b));
+        }
+    }
+}
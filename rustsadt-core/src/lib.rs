@@ -0,0 +1,24 @@
+// rustsadt-core/src/lib.rs
+// Cœur de RustSADT, sans dépendance à l'interface graphique: modèle des diagrammes,
+// règles SADT/IDEF0, persistance, exports SVG/PNG et génération de code.
+pub mod algorithm_library;
+pub mod algorithms;
+pub mod balancing;
+pub mod clipboard;
+pub mod data_dictionary;
+pub mod error;
+pub mod frame;
+pub mod generator;
+pub mod geometry;
+pub mod layout;
+pub mod numbering;
+pub mod persistence;
+pub mod pipeline;
+pub mod protected;
+pub mod reverse;
+pub mod routing;
+pub mod sadt_elements;
+pub mod sadt_model;
+pub mod simulation;
+pub mod style;
+pub mod validation;
@@ -0,0 +1,101 @@
+// rustsadt-core/src/numbering.rs
+// Numéros de boîte IDEF0 (dans le coin inférieur droit de chaque activité). Une boîte
+// reçoit à sa création le plus petit numéro libre, puis le garde: déplacer une boîte ou
+// ajouter une flèche ne la renumérote pas, seule une renumérotation explicite le fait.
+// Les boîtes sans numéro (fichiers antérieurs à la numérotation) prennent les numéros
+// libres dans l'ordre de dominance, celui des marches de l'escalier.
+use crate::layout::staircase_order;
+use crate::sadt_elements::NodeId;
+use crate::sadt_model::SadtDiagram;
+use std::collections::{HashMap, HashSet};
+
+// Numéro de chaque boîte du diagramme
+pub fn box_numbers(diagram: &SadtDiagram) -> HashMap<NodeId, u32> {
+    let mut numbers: HashMap<NodeId, u32> = diagram.nodes.values()
+        .filter_map(|node| Some((node.id, node.number?)))
+        .collect();
+    let mut next = 1;
+    for node_id in staircase_order(diagram) {
+        if numbers.contains_key(&node_id) { continue; }
+        while numbers.values().any(|n| *n == next) { next += 1; }
+        numbers.insert(node_id, next);
+    }
+    numbers
+}
+
+// Plus petit numéro qu'aucune boîte du diagramme n'a, pour une nouvelle boîte
+pub fn next_free_number(diagram: &SadtDiagram) -> u32 {
+    let used: HashSet<u32> = box_numbers(diagram).into_values().collect();
+    (1..).find(|number| !used.contains(number)).unwrap_or(1)
+}
+
+// Activités dans l'ordre de leurs numéros (à numéro égal, dans l'ordre de dominance)
+pub fn numbered_order(diagram: &SadtDiagram) -> Vec<NodeId> {
+    let numbers = box_numbers(diagram);
+    let mut order = staircase_order(diagram);
+    order.sort_by_key(|id| numbers[id]); // Tri stable: l'ordre de dominance départage
+    order
+}
+
+// Fixe les numéros 1, 2, 3... dans l'ordre actuel: les trous et les doublons
+// disparaissent, l'ordre relatif des boîtes ne change pas
+pub fn renumber(diagram: &mut SadtDiagram) {
+    for (index, node_id) in numbered_order(diagram).into_iter().enumerate() {
+        if let Some(node) = diagram.get_node_mut(node_id) {
+            node.number = Some(index as u32 + 1);
+        }
+    }
+}
+
+// Fixe les numéros 1, 2, 3... dans l'ordre de dominance (numéros choisis à la main abandonnés)
+pub fn renumber_by_dominance(diagram: &mut SadtDiagram) {
+    for node in diagram.nodes.values_mut() {
+        node.number = None;
+    }
+    renumber(diagram);
+}
+
+// Renumérote le diagramme et tous ses sous-diagrammes
+// (`automatic`: dans l'ordre de dominance plutôt que l'ordre actuel)
+pub fn renumber_hierarchy(diagram: &mut SadtDiagram, automatic: bool) {
+    if automatic { renumber_by_dominance(diagram) } else { renumber(diagram) }
+    for node in diagram.nodes.values_mut() {
+        if let Some(child) = node.child_diagram.as_mut() {
+            renumber_hierarchy(child, automatic);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{vec2, Point};
+    use crate::sadt_elements::{ArrowType, ConnectionPoint, Side};
+
+    #[test]
+    fn new_boxes_keep_their_number() {
+        let mut diagram = SadtDiagram::new();
+        let first = diagram.add_node("Premier".to_string(), Point::new(0.0, 0.0));
+        let second = diagram.add_node("Second".to_string(), Point::new(300.0, 0.0));
+        let before = box_numbers(&diagram);
+        assert_eq!((before[&first], before[&second]), (1, 2));
+
+        // Le second passe devant et alimente le premier: l'ordre de dominance s'inverse
+        diagram.translate_nodes(&[second], vec2(-600.0, 0.0));
+        let source = ConnectionPoint::new(second, Side::Right);
+        diagram.add_arrow(source, ConnectionPoint::new(first, Side::Left), ArrowType::Output, None);
+        assert_eq!(box_numbers(&diagram), before);
+
+        renumber_by_dominance(&mut diagram);
+        assert_eq!((diagram.nodes[&second].number, diagram.nodes[&first].number), (Some(1), Some(2)));
+    }
+
+    #[test]
+    fn new_box_takes_lowest_free_number() {
+        let mut diagram = SadtDiagram::new();
+        let ids: Vec<NodeId> = (0..3).map(|i| diagram.add_node(format!("A{}", i), Point::new(i as f32 * 200.0, 0.0))).collect();
+        diagram.remove_node(ids[1]);
+        let added = diagram.add_node("Nouveau".to_string(), Point::new(0.0, 300.0));
+        assert_eq!(diagram.nodes[&added].number, Some(2));
+    }
+}
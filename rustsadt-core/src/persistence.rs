@@ -0,0 +1,465 @@
+use crate::error::Result;
+use crate::error::RustSadtError;
+use crate::frame::{DiagramReference, Idef0Frame};
+use crate::numbering::box_numbers;
+use crate::generator::to_rust_ident;
+use crate::sadt_model::{SadtDiagram, DEFAULT_FRAME_SIZE};
+use crate::geometry::{vec2, Point, Rect};
+use crate::routing::path_midpoint;
+use crate::sadt_elements::{ArrowType, Side};
+use crate::style::{self, Color, ExportTheme};
+use std::fs::File;
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+// Ajouts pour SVG
+use svg::node::element::{Circle, Definitions, Marker, Polygon, Polyline, Rectangle, TSpan, Text as SvgText}; // Renommer Text pour éviter conflit
+use svg::Document;
+
+pub const FILE_EXTENSION: &str = "ron";
+pub const SVG_FILE_EXTENSION: &str = "svg";
+pub const PNG_FILE_EXTENSION: &str = "png";
+const SVG_REFERENCE_DPI: f32 = 96.0; // Résolution de référence des unités SVG
+const SVG_EXPORT_MARGIN: f32 = 20.0; // Marge autour du contenu exporté
+// Polices essayées dans l'ordre pour le rendu PNG (la première est celle du canvas egui)
+const PREFERRED_FONT_FAMILIES: [&str; 6] = ["Ubuntu", "DejaVu Sans", "Liberation Sans", "Noto Sans", "Arial", "Helvetica"];
+const ARROW_LABEL_RAISE: f32 = style::ARROW_STROKE_WIDTH + 2.0; // Écart entre la ligne et la base de son label
+
+// Sauvegarde le diagramme dans un fichier RON
+pub fn save_diagram(diagram: &SadtDiagram, path: &PathBuf) -> Result<()> {
+    let ron_string = ron::ser::to_string_pretty(diagram, ron::ser::PrettyConfig::default())?;
+    let mut file = File::create(path)?;
+    file.write_all(ron_string.as_bytes())?;
+    log::info!("Diagramme sauvegardé dans: {}", path.display());
+    Ok(())
+}
+
+// Charge un diagramme depuis un fichier RON
+pub fn load_diagram(path: &PathBuf) -> Result<SadtDiagram> {
+    let mut file = File::open(path)?;
+    let mut ron_string = String::new();
+    file.read_to_string(&mut ron_string)?;
+    let mut diagram: SadtDiagram = ron::from_str(&ron_string)?;
+    diagram.number_legacy_boundaries();
+     log::info!("Diagramme chargé depuis: {}", path.display());
+    Ok(diagram)
+}
+
+// Exporte le diagramme en SVG (`reference`: place du diagramme dans la hiérarchie, pour le cartouche)
+pub fn export_svg(diagram: &SadtDiagram, reference: &DiagramReference, path: &PathBuf, theme: ExportTheme) -> Result<()> {
+    let document = render_svg_document(diagram, reference, theme, true);
+    svg::save(path, &document).map_err(RustSadtError::Io)?;
+    log::info!("Diagramme exporté en SVG dans: {}", path.display());
+    Ok(())
+}
+
+fn marker_id(arrow_type: ArrowType) -> &'static str {
+    match arrow_type {
+        ArrowType::Input => "arrowhead-input",
+        ArrowType::Output => "arrowhead-output",
+        ArrowType::Control => "arrowhead-control",
+        ArrowType::Mechanism => "arrowhead-mechanism",
+    }
+}
+
+// Position du code ICOM à l'extrémité ouverte: (point d'ancrage, text-anchor, décalage vertical)
+fn icom_label_placement(open_end: Point, side: Side) -> (Point, &'static str, &'static str) {
+    let offset = style::ICOM_LABEL_OFFSET;
+    match side {
+        Side::Left => (open_end + vec2(-offset, 0.0), "end", ".3em"),
+        Side::Right => (open_end + vec2(offset, 0.0), "start", ".3em"),
+        Side::Top => (open_end + vec2(0.0, -offset), "middle", "0"),
+        Side::Bottom => (open_end + vec2(0.0, offset), "middle", ".8em"),
+    }
+}
+
+// Zone occupée par le diagramme (nœuds, flèches, cartouche et textes), marges comprises
+fn svg_content_bounds(diagram: &SadtDiagram, form: &Idef0Frame) -> Rect {
+    let mut bounds = diagram.nodes.values().fold(form.outer, |acc, node| acc.union(node.rect));
+    let label_height = style::LABEL_FONT_SIZE;
+    for arrow in diagram.arrows.values() {
+        let Some(path) = diagram.arrow_path(arrow) else { continue };
+        let (start, end) = (path[0], path[path.len() - 1]);
+        for point in &path {
+            bounds.extend_with(*point);
+        }
+        if let Some(label) = &arrow.label {
+            let size = vec2(style::text_width(label, style::LABEL_FONT_SIZE), label_height);
+            bounds = bounds.union(Rect::from_center_size(path_midpoint(&path) - vec2(0.0, ARROW_LABEL_RAISE + label_height / 2.0), size));
+        }
+        if let Some(code) = arrow.icom() {
+            let open_end = if arrow.source.icom().is_some() { start } else { end };
+            let (anchor, _, _) = icom_label_placement(open_end, code.side());
+            let width = style::text_width(&code.to_string(), style::LABEL_FONT_SIZE);
+            bounds = bounds.union(match code.side() {
+                Side::Left => Rect::from_min_size(anchor - vec2(width, label_height / 2.0), vec2(width, label_height)),
+                Side::Right => Rect::from_min_size(anchor - vec2(0.0, label_height / 2.0), vec2(width, label_height)),
+                Side::Top => Rect::from_center_size(anchor - vec2(0.0, label_height / 2.0), vec2(width, label_height)),
+                Side::Bottom => Rect::from_center_size(anchor + vec2(0.0, label_height / 2.0), vec2(width, label_height)),
+            });
+        }
+    }
+    if bounds.is_negative() {
+        return Rect::from_min_size(Point::ZERO, DEFAULT_FRAME_SIZE);
+    }
+    bounds.expand(SVG_EXPORT_MARGIN)
+}
+
+fn svg_outline(rect: Rect, color: Color) -> Rectangle {
+    Rectangle::new()
+        .set("x", rect.min.x)
+        .set("y", rect.min.y)
+        .set("width", rect.width())
+        .set("height", rect.height())
+        .set("fill", "none")
+        .set("stroke", color.to_css())
+        .set("stroke-width", 1)
+}
+
+// Texte dont `pos` est le coin supérieur gauche (comme sur le canvas)
+fn svg_top_left_text(text: &str, pos: Point, font_size: f32, color: Color) -> SvgText {
+    SvgText::new(text)
+        .set("x", pos.x)
+        .set("y", pos.y)
+        .set("dy", ".8em")
+        .set("fill", color.to_css())
+        .set("font-size", font_size)
+}
+
+// Cases du cartouche IDEF0: intitulés, valeurs et cases à cocher du statut
+fn add_svg_form(mut document: Document, form: &Idef0Frame, theme: ExportTheme) -> Document {
+    let stroke = theme.frame_stroke();
+    document = document.add(svg_outline(form.outer, stroke));
+    for cell in &form.cells {
+        document = document.add(svg_outline(cell.rect, stroke));
+        if !cell.caption.is_empty() {
+            document = document.add(svg_top_left_text(cell.caption, cell.caption_pos(), style::FRAME_CAPTION_FONT_SIZE, stroke));
+        }
+        if !cell.value.is_empty() {
+            document = document.add(svg_top_left_text(&cell.value, cell.value_pos(), style::FRAME_VALUE_FONT_SIZE, theme.text()));
+        }
+    }
+    for mark in &form.marks {
+        let mut square = svg_outline(mark.rect, stroke);
+        if mark.checked {
+            square = square.set("fill", theme.text().to_css());
+        }
+        document = document.add(square);
+        document = document.add(svg_top_left_text(mark.label, mark.label_pos(), style::FRAME_CAPTION_FONT_SIZE, theme.text()));
+    }
+    document
+}
+
+// Construit le document SVG d'un diagramme (partagé par les exports SVG et PNG).
+// Le rendu correspond au canvas à zoom 1, sans sélection ni prévisualisation.
+pub fn render_svg_document(diagram: &SadtDiagram, reference: &DiagramReference, theme: ExportTheme, background: bool) -> Document {
+    let frame = diagram.frame();
+    let form = Idef0Frame::new(frame, &diagram.metadata, reference);
+    let bounds = svg_content_bounds(diagram, &form);
+    let mut document = Document::new()
+        .set("width", bounds.width())
+        .set("height", bounds.height())
+        .set("viewBox", (bounds.min.x, bounds.min.y, bounds.width(), bounds.height()));
+
+    // Une pointe par type de flèche, de la couleur de la flèche
+    let mut defs = Definitions::new();
+    for arrow_type in [ArrowType::Input, ArrowType::Control, ArrowType::Output, ArrowType::Mechanism] {
+        let head = style::ARROW_HEAD_SIZE;
+        let marker = Marker::new()
+            .set("id", marker_id(arrow_type))
+            .set("markerUnits", "userSpaceOnUse")
+            .set("viewBox", (0, 0, head, head))
+            .set("refX", head) // La pointe tombe sur l'extrémité de la ligne
+            .set("refY", head / 2.0)
+            .set("markerWidth", head)
+            .set("markerHeight", head)
+            .set("orient", "auto")
+            .add(
+                Polygon::new()
+                    .set("points", format!("0,0 {},{} 0,{}", head, head / 2.0, head))
+                    .set("fill", theme.arrow_color(arrow_type).to_css()),
+            );
+        defs = defs.add(marker);
+    }
+    document = document.add(defs);
+
+    if background {
+        let background_rect = Rectangle::new()
+            .set("x", bounds.min.x)
+            .set("y", bounds.min.y)
+            .set("width", bounds.width())
+            .set("height", bounds.height())
+            .set("fill", theme.background().to_css());
+        document = document.add(background_rect);
+    }
+
+    // Cadre du diagramme et cartouche IDEF0
+    document = document.add(svg_outline(frame, theme.frame_stroke()));
+    document = add_svg_form(document, &form, theme);
+
+    // Dessiner les flèches d'abord (pour qu'elles soient en dessous)
+    for arrow in diagram.arrows.values() {
+        // Même tracé orthogonal que sur le canvas
+        if let Some(path) = diagram.arrow_path(arrow) {
+            let (start_pos, end_pos) = (path[0], path[path.len() - 1]);
+            let color_str = theme.arrow_color(arrow.arrow_type).to_css();
+
+            let points: Vec<String> = path.iter().map(|p| format!("{},{}", p.x, p.y)).collect();
+            let mut line = Polyline::new()
+                .set("points", points.join(" "))
+                .set("fill", "none")
+                .set("stroke", color_str.clone())
+                .set("stroke-width", style::ARROW_STROKE_WIDTH);
+            // Une jonction se fond dans le faisceau: pas de tête de flèche
+            if arrow.target.junction().is_none() {
+                line = line.set("marker-end", format!("url(#{})", marker_id(arrow.arrow_type)));
+            }
+            document = document.add(line);
+
+            // Point d'embranchement ou de jonction sur l'autre tracé
+            for (end, pos) in [(&arrow.source, start_pos), (&arrow.target, end_pos)] {
+                if end.junction().is_some() {
+                    let dot = Circle::new()
+                        .set("cx", pos.x)
+                        .set("cy", pos.y)
+                        .set("r", style::JUNCTION_RADIUS)
+                        .set("fill", color_str.clone());
+                    document = document.add(dot);
+                }
+            }
+
+            // Ajouter le label de la flèche
+            if let Some(label) = &arrow.label {
+                let mid = path_midpoint(&path);
+                let text = SvgText::new(label)
+                    .set("x", mid.x)
+                    .set("y", mid.y - ARROW_LABEL_RAISE) // Ligne de base un peu au-dessus de la ligne
+                    .set("fill", color_str.clone())
+                    .set("font-size", style::LABEL_FONT_SIZE)
+                    .set("text-anchor", "middle"); // Centrer le texte
+                document = document.add(text);
+            }
+
+            // Code ICOM à l'extrémité ouverte d'une flèche frontière
+            if let Some(code) = arrow.icom() {
+                let open_end = if arrow.source.icom().is_some() { start_pos } else { end_pos };
+                let (anchor_pos, anchor, dy) = icom_label_placement(open_end, code.side());
+                let text = SvgText::new(code.to_string())
+                    .set("x", anchor_pos.x)
+                    .set("y", anchor_pos.y)
+                    .set("dy", dy)
+                    .set("fill", color_str)
+                    .set("font-size", style::LABEL_FONT_SIZE)
+                    .set("text-anchor", anchor);
+                document = document.add(text);
+            }
+        }
+    }
+
+    // Dessiner les nœuds
+    let node_stroke = theme.node_stroke().to_css();
+    let numbers = box_numbers(diagram);
+    for node in diagram.nodes.values() {
+        let rect = Rectangle::new()
+            .set("x", node.rect.min.x)
+            .set("y", node.rect.min.y)
+            .set("width", node.rect.width())
+            .set("height", node.rect.height())
+            .set("rx", style::NODE_CORNER_RADIUS) // coins arrondis
+            .set("ry", style::NODE_CORNER_RADIUS)
+            .set("fill", node.fill.unwrap_or(theme.node_fill()).to_css())
+            .set("stroke", node_stroke.clone())
+            .set("stroke-width", 1);
+        document = document.add(rect);
+
+        // Double bordure pour une activité décomposée (comme sur le canvas)
+        if node.is_decomposed() {
+            let inner = node.rect.shrink(style::DECOMPOSED_INSET);
+            let inner_rect = Rectangle::new()
+                .set("x", inner.min.x)
+                .set("y", inner.min.y)
+                .set("width", inner.width())
+                .set("height", inner.height())
+                .set("rx", style::NODE_CORNER_RADIUS)
+                .set("ry", style::NODE_CORNER_RADIUS)
+                .set("fill", "none")
+                .set("stroke", node_stroke.clone())
+                .set("stroke-width", 1);
+            document = document.add(inner_rect);
+        }
+
+        // Ajouter le nom du nœud, une ligne par <tspan> (mêmes coupures que sur le canvas)
+        let lines = node.label_lines();
+        let line_height = style::NODE_FONT_SIZE * style::LINE_HEIGHT;
+        let first_y = node.rect.center().y - line_height * (lines.len().saturating_sub(1)) as f32 / 2.0;
+        let mut text = SvgText::new("")
+            .set("fill", node.fill.map_or(theme.text(), Color::contrasting_text).to_css())
+            .set("font-size", style::NODE_FONT_SIZE)
+            .set("text-anchor", "middle"); // Centrer horizontalement
+        for (index, line) in lines.into_iter().enumerate() {
+            text = text.add(TSpan::new(line)
+                .set("x", node.rect.center().x)
+                .set("y", first_y + line_height * index as f32)
+                .set("dy", ".3em")); // Ajustement vertical pour centrer
+        }
+        document = document.add(text);
+
+        // Numéro de boîte dans le coin inférieur droit
+        if let Some(number) = numbers.get(&node.id) {
+            let corner = node.rect.max - vec2(style::BOX_NUMBER_INSET, style::BOX_NUMBER_INSET);
+            let text = SvgText::new(number.to_string())
+                .set("x", corner.x)
+                .set("y", corner.y)
+                .set("fill", node.fill.map_or(theme.text(), Color::contrasting_text).to_css())
+                .set("font-size", style::LABEL_FONT_SIZE)
+                .set("text-anchor", "end");
+            document = document.add(text);
+        }
+    }
+
+    document
+}
+
+// Options de rastérisation pour l'export PNG
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PngExportOptions {
+    pub scale: f32,        // Facteur d'échelle (1.0 = une unité monde par pixel, soit 96 DPI)
+    pub transparent: bool, // Fond transparent plutôt que la couleur du thème
+    pub theme: ExportTheme,
+}
+
+impl Default for PngExportOptions {
+    fn default() -> Self {
+        Self { scale: 1.0, transparent: false, theme: ExportTheme::default() }
+    }
+}
+
+impl PngExportOptions {
+    pub fn from_dpi(dpi: f32, transparent: bool, theme: ExportTheme) -> Self {
+        Self { scale: dpi / SVG_REFERENCE_DPI, transparent, theme }
+    }
+
+    pub fn dpi(&self) -> f32 {
+        self.scale * SVG_REFERENCE_DPI
+    }
+}
+
+// Charge les polices du système et choisit une famille sans empattement proche de celle
+// du canvas (usvg utilise "Times New Roman" par défaut, souvent absente: texte non rendu)
+fn load_export_fonts(usvg_options: &mut usvg::Options) {
+    let fontdb = usvg_options.fontdb_mut();
+    fontdb.load_system_fonts();
+    let installed = |family: &str| fontdb.faces().any(|face| face.families.iter().any(|(name, _)| name == family));
+    let family = PREFERRED_FONT_FAMILIES.iter()
+        .find(|family| installed(family))
+        .map(|family| family.to_string())
+        .or_else(|| fontdb.faces().next().and_then(|face| face.families.first()).map(|(name, _)| name.clone()));
+    match family {
+        Some(family) => usvg_options.font_family = family,
+        None => log::warn!("Aucune police système trouvée: les textes ne seront pas rendus dans le PNG"),
+    }
+}
+
+// Exporte le diagramme en PNG en rastérisant la même sortie que l'export SVG
+pub fn export_png(diagram: &SadtDiagram, reference: &DiagramReference, path: &Path, options: &PngExportOptions) -> Result<()> {
+    let svg_data = render_svg_document(diagram, reference, options.theme, !options.transparent).to_string();
+
+    let mut usvg_options = usvg::Options::default();
+    load_export_fonts(&mut usvg_options);
+    let tree = usvg::Tree::from_str(&svg_data, &usvg_options)?;
+
+    let size = tree.size().to_int_size().scale_by(options.scale)
+        .ok_or_else(|| RustSadtError::Export(format!("Facteur d'échelle invalide: {}", options.scale)))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| RustSadtError::Export(format!("Image trop grande: {}x{}", size.width(), size.height())))?;
+    resvg::render(&tree, tiny_skia::Transform::from_scale(options.scale, options.scale), &mut pixmap.as_mut());
+
+    pixmap.save_png(path)?;
+    log::info!("Diagramme exporté en PNG ({}x{}) dans: {}", size.width(), size.height(), path.display());
+    Ok(())
+}
+
+// Exporte le diagramme et tous ses sous-diagrammes: la racine dans `path`,
+// chaque sous-diagramme dans un fichier voisin "<nom>_<activité>.svg".
+// Retourne la liste des fichiers écrits.
+pub fn export_svg_hierarchy(diagram: &SadtDiagram, path: &Path, theme: ExportTheme) -> Result<Vec<PathBuf>> {
+    export_hierarchy(diagram, path, &|level, reference, level_path| export_svg(level, reference, &level_path.to_path_buf(), theme))
+}
+
+// Même principe que `export_svg_hierarchy`, en PNG
+pub fn export_png_hierarchy(diagram: &SadtDiagram, path: &Path, options: &PngExportOptions) -> Result<Vec<PathBuf>> {
+    export_hierarchy(diagram, path, &|level, reference, level_path| export_png(level, reference, level_path, options))
+}
+
+type LevelExporter<'a> = dyn Fn(&SadtDiagram, &DiagramReference, &Path) -> Result<()> + 'a;
+
+fn export_hierarchy(diagram: &SadtDiagram, path: &Path, export: &LevelExporter) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    let mut used_names = HashSet::new();
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("diagram").to_string();
+    used_names.insert(stem.clone());
+    export_level(diagram, &DiagramReference::root(diagram), path, &stem, export, &mut used_names, &mut written)?;
+    Ok(written)
+}
+
+fn export_level(
+    diagram: &SadtDiagram,
+    reference: &DiagramReference,
+    path: &Path,
+    stem: &str,
+    export: &LevelExporter,
+    used_names: &mut HashSet<String>,
+    written: &mut Vec<PathBuf>,
+) -> Result<()> {
+    export(diagram, reference, path)?;
+    written.push(path.to_path_buf());
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or(SVG_FILE_EXTENSION);
+    for (node, child) in diagram.decomposed_nodes() {
+        let mut child_stem = format!("{}_{}", stem, to_rust_ident(&node.name));
+        let mut suffix = 2;
+        while !used_names.insert(child_stem.clone()) {
+            child_stem = format!("{}_{}_{}", stem, to_rust_ident(&node.name), suffix);
+            suffix += 1;
+        }
+        let child_path = path.with_file_name(format!("{}.{}", child_stem, extension));
+        export_level(child, &reference.child(diagram, node.id, child), &child_path, &child_stem, export, used_names, written)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sadt_elements::{ArrowEnd, IcomCode};
+    use std::str::FromStr;
+    use uuid::Uuid;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures").join(name)
+    }
+
+    // Fichier enregistré avant les flèches frontières: points de connexion nus,
+    // UUID nul pour l'extérieur du diagramme, pas d'algorithme
+    #[test]
+    fn loads_baseline_file() {
+        let diagram = load_diagram(&fixture("simple_process_v0.ron")).unwrap();
+        assert_eq!(diagram.nodes.len(), 2);
+        let arrow = |id: &str| diagram.get_arrow(Uuid::from_str(id).unwrap()).unwrap();
+        let internal = arrow("f1f1f1f1-f1f1-f1f1-f1f1-f1f1f1f1f1f1");
+        assert_eq!(internal.source.node_id(), Some(Uuid::from_str("a1a1a1a1-b1b1-c1c1-d1d1-e1e1e1e1e1e1").unwrap()));
+        assert_eq!(internal.target.connection_point().map(|cp| cp.side), Some(Side::Left));
+        let external = arrow("f2f2f2f2-f2f2-f2f2-f2f2-f2f2f2f2f2f2");
+        assert_eq!(external.source, ArrowEnd::Boundary(IcomCode::new(ArrowType::Input, 1)));
+    }
+
+    #[test]
+    fn saved_file_loads_back() {
+        let diagram = load_diagram(&fixture("simple_process_v0.ron")).unwrap();
+        let path = std::env::temp_dir().join(format!("rustsadt-{}.ron", Uuid::new_v4()));
+        save_diagram(&diagram, &path).unwrap();
+        let reloaded = load_diagram(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(reloaded.unwrap(), diagram);
+    }
+}
@@ -0,0 +1,97 @@
+// rustsadt-core/src/pipeline.rs
+// Ordre d'exécution des activités d'un diagramme: une activité s'exécute après
+// celles dont les sorties alimentent ses entrées, contrôles ou mécanismes.
+use crate::error::{Result, RustSadtError};
+use crate::sadt_elements::NodeId;
+use crate::sadt_model::SadtDiagram;
+use std::collections::HashSet;
+
+// Dépendances (activité source, activité cible) portées par les flèches entre deux activités.
+// Embranchements et jonctions relient l'activité qui produit la donnée à celle qui la reçoit.
+pub fn dependencies(diagram: &SadtDiagram) -> Vec<(NodeId, NodeId)> {
+    diagram.arrows.values()
+        .filter_map(|arrow| Some((diagram.origin(arrow).source.node_id()?, diagram.delivered(arrow).target.node_id()?)))
+        .collect()
+}
+
+// Tri topologique stable (à dépendances égales, ordre des noms d'activités).
+// Un cycle rend l'ordre impossible: l'erreur liste les activités concernées.
+pub fn execution_order(diagram: &SadtDiagram) -> Result<Vec<NodeId>> {
+    let mut nodes: Vec<_> = diagram.nodes.values().collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+    let edges = dependencies(diagram);
+
+    let mut order = Vec::with_capacity(nodes.len());
+    let mut remaining: HashSet<NodeId> = nodes.iter().map(|node| node.id).collect();
+    while let Some(next) = nodes.iter()
+        .map(|node| node.id)
+        .find(|id| remaining.contains(id) && !edges.iter().any(|(source, target)| target == id && remaining.contains(source)))
+    {
+        remaining.remove(&next);
+        order.push(next);
+    }
+
+    if remaining.is_empty() {
+        return Ok(order);
+    }
+
+    // Écarte les activités bloquées seulement parce qu'elles sont en aval d'un cycle
+    loop {
+        let downstream: Vec<NodeId> = remaining.iter()
+            .copied()
+            .filter(|id| !edges.iter().any(|(source, target)| source == id && remaining.contains(target)))
+            .collect();
+        if downstream.is_empty() { break; }
+        for id in downstream {
+            remaining.remove(&id);
+        }
+    }
+    let names: Vec<&str> = nodes.iter()
+        .filter(|node| remaining.contains(&node.id))
+        .map(|node| node.name.as_str())
+        .collect();
+    Err(RustSadtError::Generation(format!(
+        "Cycle dans le flux de données entre les activités: {}", names.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::sadt_elements::{ArrowType, ConnectionPoint, Side};
+
+    fn link(diagram: &mut SadtDiagram, source: NodeId, target: NodeId) {
+        diagram.add_arrow(ConnectionPoint::new(source, Side::Right), ConnectionPoint::new(target, Side::Left), ArrowType::Output, None).unwrap();
+    }
+
+    // Le flux de données prime sur l'ordre des noms
+    #[test]
+    fn activities_follow_data_flow() {
+        let mut diagram = SadtDiagram::new();
+        let read = diagram.add_node("C lire".to_string(), Point::ZERO);
+        let sort = diagram.add_node("B trier".to_string(), Point::new(200.0, 0.0));
+        let print = diagram.add_node("A imprimer".to_string(), Point::new(400.0, 0.0));
+        let alone = diagram.add_node("D isolée".to_string(), Point::new(600.0, 0.0));
+        link(&mut diagram, read, sort);
+        link(&mut diagram, sort, print);
+        assert_eq!(execution_order(&diagram).unwrap(), [read, sort, print, alone]);
+    }
+
+    // Seules les activités du cycle sont citées, pas celles en aval
+    #[test]
+    fn cycle_names_its_activities() {
+        let mut diagram = SadtDiagram::new();
+        let start = diagram.add_node("Départ".to_string(), Point::ZERO);
+        let first = diagram.add_node("Mesurer".to_string(), Point::new(200.0, 0.0));
+        let second = diagram.add_node("Corriger".to_string(), Point::new(400.0, 0.0));
+        let after = diagram.add_node("Publier".to_string(), Point::new(600.0, 0.0));
+        link(&mut diagram, start, first);
+        link(&mut diagram, first, second);
+        link(&mut diagram, second, first);
+        link(&mut diagram, second, after);
+        let error = execution_order(&diagram).unwrap_err().to_string();
+        assert!(error.contains("Corriger, Mesurer"), "{}", error);
+        assert!(!error.contains("Départ") && !error.contains("Publier"), "{}", error);
+    }
+}
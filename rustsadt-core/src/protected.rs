@@ -0,0 +1,323 @@
+// rustsadt-core/src/protected.rs
+// Régions protégées du code généré: le corps de chaque fonction d'activité est
+// encadré par des marqueurs portant l'UUID du nœud,
+//
+//     // rustsadt:begin <uuid> signature:<empreinte> body:<empreinte>
+//     ...
+//     // rustsadt:end <uuid>
+//
+// Lors d'une régénération dans un fichier existant, un corps modifié à la main
+// (empreinte différente de celle enregistrée) est conservé; tout le reste du
+// fichier (signatures, commentaires, `run_process`) est régénéré: le code écrit à la
+// main hors des régions (imports, fonctions utilitaires...) est signalé dans le bilan.
+use crate::error::{Result, RustSadtError};
+use crate::reverse::tokens_text;
+use std::collections::{HashMap, HashSet};
+use syn::Item;
+
+const BEGIN: &str = "// rustsadt:begin ";
+const END: &str = "// rustsadt:end ";
+const ORPHANS_HEADER: &str = "// --- rustsadt: code conservé d'activités supprimées du diagramme ---";
+const ORPHAN: &str = "// rustsadt:orphan ";
+
+// Bilan d'une régénération dans un fichier existant
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    pub preserved: Vec<String>, // Fonctions dont le corps écrit à la main a été conservé
+    pub conflicts: Vec<String>, // Corps conservés à vérifier (signature modifiée, activité supprimée)
+}
+
+struct Region<'a> {
+    id: &'a str,
+    signature: Option<&'a str>,
+    body_hash: Option<&'a str>,
+    function: String, // Nom de la fonction (pour les messages)
+    body: Vec<&'a str>,
+}
+
+impl Region<'_> {
+    // Corps inchangé depuis la génération
+    fn is_pristine(&self) -> bool {
+        self.body_hash == Some(fingerprint(&self.body).as_str())
+    }
+}
+
+// Complète les marqueurs `begin` du code généré avec les empreintes de la signature et du corps
+pub fn seal(code: &str) -> String {
+    let mut out = Vec::new();
+    let mut signature: Vec<&str> = Vec::new();
+    let mut lines = code.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("pub fn ") {
+            signature.clear();
+        }
+        signature.push(line);
+        let Some(id) = trimmed.strip_prefix(BEGIN).and_then(|rest| rest.split_whitespace().next()) else {
+            out.push(line.to_string());
+            continue;
+        };
+
+        let mut body = Vec::new();
+        let mut end = None;
+        for body_line in lines.by_ref() {
+            if body_line.trim_start().starts_with(END) { end = Some(body_line); break; }
+            body.push(body_line);
+        }
+        let indent = &line[..line.len() - trimmed.len()];
+        let signature_lines = &signature[..signature.len() - 1];
+        out.push(format!("{}{}{} signature:{} body:{}", indent, BEGIN, id, fingerprint(signature_lines), fingerprint(&body)));
+        out.extend(body.iter().map(|l| l.to_string()));
+        out.extend(end.map(str::to_string));
+    }
+    join_lines(out, code)
+}
+
+// Régénère `existing` à partir de `generated` (déjà scellé) en conservant les corps modifiés
+pub fn merge(existing: &str, generated: &str) -> Result<(String, MergeReport)> {
+    let mut previous: HashMap<&str, Region> = parse_regions(existing)?.into_iter().map(|r| (r.id, r)).collect();
+    let mut report = MergeReport::default();
+    let mut out = Vec::new();
+
+    let mut lines = generated.lines();
+    let regions: HashMap<&str, Region> = parse_regions(generated)?.into_iter().map(|r| (r.id, r)).collect();
+    while let Some(line) = lines.next() {
+        let Some(id) = line.trim_start().strip_prefix(BEGIN).and_then(|rest| rest.split_whitespace().next()) else {
+            out.push(line.to_string());
+            continue;
+        };
+        let new = &regions[id];
+        // Le corps généré est remplacé par le corps conservé, marqueur de fin compris
+        let generated_body: Vec<&str> = lines.by_ref().take_while(|l| !l.trim_start().starts_with(END)).collect();
+        let end_line = format!("{}{}{}", indent_of(line), END, id);
+        match previous.remove(id).filter(|old| !old.is_pristine()) {
+            Some(old) => {
+                report.preserved.push(new.function.clone());
+                if old.signature != new.signature {
+                    report.conflicts.push(format!(
+                        "La signature de `{}` a changé: vérifiez le corps conservé", new.function));
+                }
+                // L'empreinte d'origine reste: le corps est toujours considéré comme modifié
+                out.push(format!("{}{}{} signature:{} body:{}", indent_of(line), BEGIN, id,
+                    new.signature.unwrap_or_default(), old.body_hash.unwrap_or_default()));
+                out.extend(old.body.iter().map(|l| l.to_string()));
+            }
+            None => {
+                out.push(line.to_string());
+                out.extend(generated_body.iter().map(|l| l.to_string()));
+            }
+        }
+        out.push(end_line);
+    }
+
+    // Corps modifiés d'activités disparues: conservés en commentaire en fin de fichier,
+    // à la suite de ceux des régénérations précédentes
+    let kept_orphans: Vec<&str> = existing.lines().skip_while(|l| *l != ORPHANS_HEADER).skip(1).collect();
+    let mut orphans: Vec<Region> = previous.into_values().filter(|old| !old.is_pristine()).collect();
+    orphans.sort_by(|a, b| a.function.cmp(&b.function));
+    if !kept_orphans.is_empty() || !orphans.is_empty() {
+        while out.last().is_some_and(|l| l.trim().is_empty()) { out.pop(); }
+        out.push(String::new());
+        out.push(ORPHANS_HEADER.to_string());
+        out.extend(kept_orphans.iter().map(|l| l.to_string()));
+        for orphan in orphans {
+            report.conflicts.push(format!(
+                "L'activité de `{}` n'existe plus: son corps est conservé en commentaire en fin de fichier", orphan.function));
+            out.push(format!("{}{} {}", ORPHAN, orphan.id, orphan.function));
+            out.extend(orphan.body.iter().map(|l| format!("// {}", l).trim_end().to_string()));
+        }
+    }
+    // Code hors des régions qui disparaît: écrit à la main, la régénération le supprime
+    let activities: HashSet<String> = region_ids(existing).into_iter().map(|(function, _)| function).collect();
+    match (syn::parse_file(existing), syn::parse_file(generated)) {
+        (Ok(old), Ok(new)) => {
+            for item in dropped_items(&old.items, &new.items, &activities) {
+                report.conflicts.push(format!(
+                    "`{}` ne fait pas partie du code généré et a été supprimé (le code écrit à la main doit rester dans les régions protégées)", item));
+            }
+        }
+        (Err(_), _) => report.conflicts.push(
+            "Le fichier existant n'est pas du Rust valide: le code écrit hors des régions protégées n'a pas pu être vérifié".to_string()),
+        (_, Err(_)) => {}
+    }
+    Ok((join_lines(out, generated), report))
+}
+
+// Éléments de `old` absents de `new`, sous-modules compris. Les fonctions d'activités
+// (et les sous-modules qui en contiennent) relèvent des régions protégées.
+fn dropped_items(old: &[Item], new: &[Item], activities: &HashSet<String>) -> Vec<String> {
+    let mut dropped = Vec::new();
+    for item in old {
+        let Some(label) = item_label(item) else { continue };
+        let kept = new.iter().find(|other| item_label(other).as_ref() == Some(&label));
+        match (item, kept) {
+            (Item::Mod(module), Some(Item::Mod(kept))) => {
+                let (Some((_, items)), Some((_, kept_items))) = (&module.content, &kept.content) else { continue };
+                dropped.extend(dropped_items(items, kept_items, activities).into_iter().map(|inner| format!("{}::{}", module.ident, inner)));
+            }
+            (_, Some(_)) => {}
+            (Item::Fn(function), None) if activities.contains(&function.sig.ident.to_string()) => {}
+            (Item::Mod(module), None) if contains_activity(module, activities) => {}
+            (_, None) => dropped.push(label),
+        }
+    }
+    dropped
+}
+
+fn contains_activity(module: &syn::ItemMod, activities: &HashSet<String>) -> bool {
+    module.content.iter().flat_map(|(_, items)| items).any(|item| match item {
+        Item::Fn(function) => activities.contains(&function.sig.ident.to_string()),
+        Item::Mod(inner) => contains_activity(inner, activities),
+        _ => false,
+    })
+}
+
+// Désignation d'un élément, qui l'identifie d'une génération à l'autre
+fn item_label(item: &Item) -> Option<String> {
+    Some(match item {
+        Item::Fn(function) => format!("fn {}", function.sig.ident),
+        Item::Mod(module) => format!("mod {}", module.ident),
+        Item::Struct(item) => format!("struct {}", item.ident),
+        Item::Enum(item) => format!("enum {}", item.ident),
+        Item::Type(item) => format!("type {}", item.ident),
+        Item::Const(item) => format!("const {}", item.ident),
+        Item::Static(item) => format!("static {}", item.ident),
+        Item::Trait(item) => format!("trait {}", item.ident),
+        Item::Use(item) => format!("use {}", tokens_text(&item.tree)),
+        Item::Impl(item) => match &item.trait_ {
+            Some((_, path, _)) => format!("impl {} for {}", tokens_text(path), tokens_text(&item.self_ty)),
+            None => format!("impl {}", tokens_text(&item.self_ty)),
+        },
+        Item::Macro(item) => format!("{}!", tokens_text(&item.mac.path)),
+        _ => return None,
+    })
+}
+
+// Régions du code, dans l'ordre du fichier: (nom de la fonction, UUID du nœud)
+pub fn region_ids(code: &str) -> Vec<(String, String)> {
+    parse_regions(code).unwrap_or_default().into_iter().map(|r| (r.function, r.id.to_string())).collect()
+}
+
+fn parse_regions(code: &str) -> Result<Vec<Region<'_>>> {
+    let mut regions = Vec::new();
+    let mut function = String::new();
+    let mut lines = code.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if trimmed == ORPHANS_HEADER { break; }
+        if let Some(rest) = trimmed.strip_prefix("pub fn ") {
+            function = rest.split(['(', '<']).next().unwrap_or_default().to_string();
+        }
+        let Some(rest) = trimmed.strip_prefix(BEGIN) else { continue };
+        let mut fields = rest.split_whitespace();
+        let id = fields.next().unwrap_or_default();
+        let mut region = Region { id, signature: None, body_hash: None, function: function.clone(), body: Vec::new() };
+        for field in fields {
+            if let Some(value) = field.strip_prefix("signature:") { region.signature = Some(value); }
+            if let Some(value) = field.strip_prefix("body:") { region.body_hash = Some(value); }
+        }
+        let mut closed = false;
+        for body_line in lines.by_ref() {
+            if body_line.trim_start().starts_with(END) { closed = true; break; }
+            region.body.push(body_line);
+        }
+        if !closed {
+            return Err(RustSadtError::Generation(format!("Marqueur de fin manquant pour la région {} ({})", id, region.function)));
+        }
+        regions.push(region);
+    }
+    Ok(regions)
+}
+
+fn indent_of(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+// Conserve le saut de ligne final du texte d'origine
+fn join_lines(lines: Vec<String>, original: &str) -> String {
+    let mut text = lines.join("\n");
+    if original.ends_with('\n') { text.push('\n'); }
+    text
+}
+
+// Empreinte FNV-1a 64 bits d'un bloc de lignes, insensible aux espaces de fin de ligne
+fn fingerprint(lines: &[&str]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for line in lines {
+        for byte in line.trim_end().bytes().chain([b'\n']) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "a1a1a1a1-b1b1-c1c1-d1d1-e1e1e1e1e1e1";
+
+    fn module(signature: &str, body: &str) -> String {
+        seal(&format!("{} {{\n    {}{}\n{}\n    {}{}\n}}\n", signature, BEGIN, ID, body, END, ID))
+    }
+
+    // Un corps resté tel que généré suit la régénération
+    #[test]
+    fn pristine_body_is_regenerated() {
+        let existing = module("pub fn traiter(x: u32) -> u32", "    todo!()");
+        let generated = module("pub fn traiter(x: u32) -> u32", "    todo!(\"nouveau\")");
+        let (merged, report) = merge(&existing, &generated).unwrap();
+        assert_eq!(merged, generated);
+        assert_eq!(report, MergeReport::default());
+    }
+
+    // Un corps écrit à la main est conservé; une signature modifiée est signalée
+    #[test]
+    fn hand_written_body_is_kept() {
+        let generated = module("pub fn traiter(x: u32) -> u32", "    todo!()");
+        let existing = generated.replace("    todo!()", "    x * 2");
+        let (merged, report) = merge(&existing, &module("pub fn traiter(x: u64) -> u64", "    todo!()")).unwrap();
+        assert!(merged.contains("pub fn traiter(x: u64) -> u64") && merged.contains("    x * 2"));
+        assert!(!merged.contains("todo!()"));
+        assert_eq!(report.preserved, ["traiter"]);
+        assert_eq!(report.conflicts.len(), 1);
+
+        // Le corps conservé reste marqué comme modifié lors de la régénération suivante
+        let (again, _) = merge(&merged, &generated).unwrap();
+        assert!(again.contains("    x * 2"));
+    }
+
+    // Le corps modifié d'une activité supprimée finit en commentaire
+    #[test]
+    fn removed_activity_body_becomes_orphan() {
+        let existing = module("pub fn traiter(x: u32) -> u32", "    todo!()").replace("    todo!()", "    x * 2");
+        let (merged, report) = merge(&existing, "pub fn run_process() {}\n").unwrap();
+        assert!(merged.contains(ORPHANS_HEADER));
+        assert!(merged.contains(&format!("{}{} traiter", ORPHAN, ID)));
+        assert!(merged.contains("//     x * 2"));
+        assert_eq!(report.conflicts.len(), 1);
+        assert!(region_ids(&merged).is_empty());
+    }
+
+    // Imports et fonctions utilitaires ajoutés hors des régions sont signalés, sous-modules compris
+    #[test]
+    fn code_outside_regions_is_reported() {
+        let generated = format!("pub mod etape {{\n{}}}\npub fn run_process() {{}}\n", module("pub fn traiter(x: u32) -> u32", "    todo!()"));
+        let existing = generated
+            .replace("pub mod etape {\n", "use std::fmt;\npub mod etape {\n    fn aide() {}\n")
+            .replace("pub fn run_process", "impl fmt::Debug for Etat {}\npub fn run_process");
+        let (merged, report) = merge(&existing, &generated).unwrap();
+        assert_eq!(merged, generated);
+        assert_eq!(report.conflicts.len(), 3, "{:?}", report.conflicts);
+        for item in ["`use std::fmt`", "`etape::fn aide`", "`impl fmt::Debug for Etat`"] {
+            assert!(report.conflicts.iter().any(|conflict| conflict.starts_with(item)), "{} {:?}", item, report.conflicts);
+        }
+    }
+
+    #[test]
+    fn missing_end_marker_is_an_error() {
+        let code = format!("pub fn traiter() {{\n    {}{}\n}}\n", BEGIN, ID);
+        assert!(merge(&code, &code).is_err());
+    }
+}
@@ -0,0 +1,328 @@
+// rustsadt-core/src/routing.rs
+// Routage orthogonal des flèches (angles droits, convention IDEF0). Sans point de
+// passage, le tracé contourne les boîtes: plus court chemin sur une grille construite
+// à partir des bords des boîtes, chaque coude étant pénalisé. Avec des points de
+// passage, le tracé les relie par des segments horizontaux et verticaux.
+use crate::geometry::{Point, Rect, Vec2};
+use crate::sadt_elements::Side;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+pub const ROUTE_CLEARANCE: f32 = 15.0; // Distance entre une flèche et les boîtes qu'elle contourne
+const BEND_PENALTY: f32 = 25.0; // Coût d'un coude, en unités monde
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const JUNCTION_STEP: f32 = 5.0; // Pas des positions essayées le long d'un tracé pour une jonction
+
+// Extrémité d'un tracé: position et sens de parcours de la flèche (horizontal ou vertical)
+#[derive(Debug, Clone, Copy)]
+pub struct RouteEnd {
+    pub pos: Point,
+    pub dir: Vec2,
+}
+
+// Normale sortante d'un côté de boîte (ou du cadre)
+pub fn side_normal(side: Side) -> Vec2 {
+    match side {
+        Side::Left => Vec2::new(-1.0, 0.0),
+        Side::Right => Vec2::new(1.0, 0.0),
+        Side::Top => Vec2::new(0.0, -1.0),
+        Side::Bottom => Vec2::new(0.0, 1.0),
+    }
+}
+
+// Polyligne de `start` à `end`, passant par les points de passage s'il y en a
+pub fn route(start: RouteEnd, end: RouteEnd, waypoints: &[Point], obstacles: &[Rect]) -> Vec<Point> {
+    let path = if waypoints.is_empty() {
+        auto_route(start, end, obstacles)
+    } else {
+        let mut points = vec![start.pos];
+        points.extend_from_slice(waypoints);
+        points.push(end.pos);
+        orthogonalize(&points, start.dir.x != 0.0)
+    };
+    simplify(path)
+}
+
+fn auto_route(start: RouteEnd, end: RouteEnd, obstacles: &[Rect]) -> Vec<Point> {
+    // La flèche quitte sa boîte et arrive sur la suivante perpendiculairement au côté
+    let from = start.pos + start.dir * ROUTE_CLEARANCE;
+    let to = end.pos - end.dir * ROUTE_CLEARANCE;
+    let blocked: Vec<Rect> = obstacles.iter().map(|rect| rect.expand(ROUTE_CLEARANCE / 2.0)).collect();
+    let middle = grid_route(from, start.dir, to, end.dir, &blocked)
+        .unwrap_or_else(|| orthogonalize(&[from, to], start.dir.x != 0.0));
+    let mut path = vec![start.pos];
+    path.extend(middle);
+    path.push(end.pos);
+    path
+}
+
+// Dijkstra sur les intersections des couloirs longeant les boîtes; l'état mémorise
+// la direction d'arrivée pour pénaliser les coudes (et interdire les demi-tours)
+fn grid_route(from: Point, from_dir: Vec2, to: Point, to_dir: Vec2, blocked: &[Rect]) -> Option<Vec<Point>> {
+    if blocked.iter().any(|rect| strictly_contains(rect, from) || strictly_contains(rect, to)) {
+        return None;
+    }
+    let lane = ROUTE_CLEARANCE / 2.0;
+    let xs = lanes([from.x, to.x, (from.x + to.x) / 2.0].into_iter()
+        .chain(blocked.iter().flat_map(|rect| [rect.left() - lane, rect.right() + lane])));
+    let ys = lanes([from.y, to.y, (from.y + to.y) / 2.0].into_iter()
+        .chain(blocked.iter().flat_map(|rect| [rect.top() - lane, rect.bottom() + lane])));
+    let find = |lanes: &[f32], v: f32| lanes.iter().position(|l| (l - v).abs() < 0.01);
+    let (start, goal) = ((find(&xs, from.x)?, find(&ys, from.y)?), (find(&xs, to.x)?, find(&ys, to.y)?));
+
+    let cell = |ix: usize, iy: usize| iy * xs.len() + ix;
+    let state_count = xs.len() * ys.len() * DIRECTIONS.len();
+    let finish = state_count; // État virtuel: arrivé, dans le sens attendu
+    let mut cost = vec![f32::INFINITY; state_count + 1];
+    let mut previous = vec![usize::MAX; state_count + 1];
+    let mut queue = BinaryHeap::new();
+    let first = cell(start.0, start.1) * 4 + direction_index(from_dir);
+    cost[first] = 0.0;
+    queue.push(Visit { cost: 0.0, state: first });
+
+    while let Some(Visit { cost: current, state }) = queue.pop() {
+        if state == finish { break; }
+        if current > cost[state] { continue; }
+        let (position, dir) = (state / 4, state % 4);
+        let (ix, iy) = (position % xs.len(), position / xs.len());
+        let mut relax = |next: usize, next_cost: f32, queue: &mut BinaryHeap<Visit>| {
+            if next_cost < cost[next] {
+                cost[next] = next_cost;
+                previous[next] = state;
+                queue.push(Visit { cost: next_cost, state: next });
+            }
+        };
+        if (ix, iy) == goal {
+            let target = direction_index(to_dir);
+            let penalty = if dir == target { 0.0 } else if dir == opposite(target) { 4.0 * BEND_PENALTY } else { BEND_PENALTY };
+            relax(finish, current + penalty, &mut queue);
+        }
+        for (next_dir, (dx, dy)) in DIRECTIONS.iter().enumerate() {
+            if next_dir == opposite(dir) { continue; }
+            let (nx, ny) = (ix as i32 + dx, iy as i32 + dy);
+            if nx < 0 || ny < 0 || nx as usize >= xs.len() || ny as usize >= ys.len() { continue; }
+            let (nx, ny) = (nx as usize, ny as usize);
+            let (a, b) = (Point::new(xs[ix], ys[iy]), Point::new(xs[nx], ys[ny]));
+            if blocked.iter().any(|rect| strictly_contains(rect, b) || crosses(rect, a, b)) { continue; }
+            let bend = if next_dir == dir { 0.0 } else { BEND_PENALTY };
+            relax(cell(nx, ny) * 4 + next_dir, current + a.distance(b) + bend, &mut queue);
+        }
+    }
+
+    if previous[finish] == usize::MAX { return None; }
+    let mut points = Vec::new();
+    let mut state = previous[finish];
+    while state != usize::MAX {
+        let position = state / 4;
+        points.push(Point::new(xs[position % xs.len()], ys[position / xs.len()]));
+        state = previous[state];
+    }
+    points.reverse();
+    Some(points)
+}
+
+// Élément de la file de priorité (le moins coûteux d'abord)
+struct Visit {
+    cost: f32,
+    state: usize,
+}
+
+impl PartialEq for Visit {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Visit {}
+
+impl PartialOrd for Visit {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Visit {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost).then(other.state.cmp(&self.state))
+    }
+}
+
+fn lanes(values: impl Iterator<Item = f32>) -> Vec<f32> {
+    let mut lanes: Vec<f32> = values.collect();
+    lanes.sort_by(f32::total_cmp);
+    lanes.dedup_by(|a, b| (*a - *b).abs() < 0.01);
+    lanes
+}
+
+fn direction_index(dir: Vec2) -> usize {
+    if dir.x.abs() >= dir.y.abs() {
+        if dir.x >= 0.0 { 0 } else { 1 }
+    } else if dir.y >= 0.0 { 2 } else { 3 }
+}
+
+fn opposite(dir: usize) -> usize {
+    dir ^ 1
+}
+
+fn strictly_contains(rect: &Rect, p: Point) -> bool {
+    p.x > rect.left() && p.x < rect.right() && p.y > rect.top() && p.y < rect.bottom()
+}
+
+// Un segment horizontal ou vertical traverse-t-il l'intérieur du rectangle ?
+fn crosses(rect: &Rect, a: Point, b: Point) -> bool {
+    if a.y == b.y {
+        a.y > rect.top() && a.y < rect.bottom() && a.x.min(b.x) < rect.right() && a.x.max(b.x) > rect.left()
+    } else {
+        a.x > rect.left() && a.x < rect.right() && a.y.min(b.y) < rect.bottom() && a.y.max(b.y) > rect.top()
+    }
+}
+
+// Relie les points par des segments horizontaux et verticaux, en ajoutant un coude
+// entre deux points non alignés (le premier segment part dans le sens `horizontal`)
+fn orthogonalize(points: &[Point], mut horizontal: bool) -> Vec<Point> {
+    let mut path: Vec<Point> = points.first().copied().into_iter().collect();
+    for pair in points.windows(2) {
+        let (a, b) = (pair[0], pair[1]);
+        if a.x != b.x && a.y != b.y {
+            path.push(if horizontal { Point::new(b.x, a.y) } else { Point::new(a.x, b.y) });
+        } else {
+            horizontal = a.y != b.y; // Après un segment vertical, on repart à l'horizontale
+        }
+        path.push(b);
+    }
+    path
+}
+
+// Retire les points confondus et les points intermédiaires d'un même segment
+fn simplify(points: Vec<Point>) -> Vec<Point> {
+    let mut path: Vec<Point> = Vec::with_capacity(points.len());
+    for p in points {
+        if path.last().is_some_and(|last| last.distance_sq(p) < 1e-4) { continue; }
+        if let [.., a, b] = path[..] {
+            let aligned = (a.x == b.x && b.x == p.x) || (a.y == b.y && b.y == p.y);
+            if aligned { path.pop(); }
+        }
+        path.push(p);
+    }
+    path
+}
+
+// Point situé à mi-longueur de la polyligne (position du label)
+pub fn path_midpoint(path: &[Point]) -> Point {
+    let total: f32 = path.windows(2).map(|s| s[0].distance(s[1])).sum();
+    let mut remaining = total / 2.0;
+    for segment in path.windows(2) {
+        let length = segment[0].distance(segment[1]);
+        if length >= remaining && length > 0.0 {
+            return segment[0].lerp(segment[1], remaining / length);
+        }
+        remaining -= length;
+    }
+    path.first().copied().unwrap_or(Point::ZERO)
+}
+
+// Distance² d'un point à la polyligne
+pub fn distance_sq_to_path(p: Point, path: &[Point]) -> f32 {
+    path.windows(2).map(|s| distance_sq_to_segment(p, s[0], s[1])).fold(f32::INFINITY, f32::min)
+}
+
+// Indice du segment de la polyligne le plus proche du point
+pub fn closest_segment(p: Point, path: &[Point]) -> Option<usize> {
+    path.windows(2).enumerate()
+        .min_by(|(_, a), (_, b)| distance_sq_to_segment(p, a[0], a[1]).total_cmp(&distance_sq_to_segment(p, b[0], b[1])))
+        .map(|(index, _)| index)
+}
+
+// Point d'embranchement sur un tracé, au plus près de `toward`, et direction qui s'en
+// écarte perpendiculairement au segment, du côté de `toward`. Le point reste à
+// `ROUTE_CLEARANCE` des extrémités et des coudes du tracé, pour ne pas se confondre
+// avec une boîte ni prolonger le segment suivant, et la flèche n'y part pas droit dans
+// une boîte (sur un tracé trop court ou trop encombré, il est au milieu).
+pub fn junction_point(path: &[Point], toward: Point, obstacles: &[Rect]) -> Option<(Point, Vec2)> {
+    let blocked: Vec<Rect> = obstacles.iter().map(|rect| rect.expand(ROUTE_CLEARANCE / 2.0)).collect();
+    let leaving = |pos: Point, a: Point, b: Point| {
+        if (b.y - a.y).abs() < (b.x - a.x).abs() {
+            Vec2::new(0.0, if toward.y < pos.y { -1.0 } else { 1.0 })
+        } else {
+            Vec2::new(if toward.x < pos.x { -1.0 } else { 1.0 }, 0.0)
+        }
+    };
+    let mut best: Option<(Point, Vec2)> = None;
+    for segment in path.windows(2) {
+        let (a, b) = (segment[0], segment[1]);
+        let length = a.distance(b);
+        if length < 2.0 * ROUTE_CLEARANCE { continue; }
+        let unit = (b - a).normalized();
+        let projected = a.distance(project_on_segment(toward, a, b)).clamp(ROUTE_CLEARANCE, length - ROUTE_CLEARANCE);
+        let steps = ((length - 2.0 * ROUTE_CLEARANCE) / JUNCTION_STEP) as usize;
+        let positions = std::iter::once(projected).chain((0..=steps).map(|step| ROUTE_CLEARANCE + step as f32 * JUNCTION_STEP));
+        for along in positions {
+            let pos = a + unit * along;
+            let dir = leaving(pos, a, b);
+            if blocked.iter().any(|rect| rect.contains(pos + dir * ROUTE_CLEARANCE)) { continue; }
+            if best.is_none_or(|(other, _)| pos.distance_sq(toward) < other.distance_sq(toward)) {
+                best = Some((pos, dir));
+            }
+        }
+    }
+    best.or_else(|| {
+        let middle = path_midpoint(path);
+        let index = closest_segment(middle, path)?;
+        Some((middle, leaving(middle, path[index], path[index + 1])))
+    })
+}
+
+fn distance_sq_to_segment(p: Point, a: Point, b: Point) -> f32 {
+    p.distance_sq(project_on_segment(p, a, b))
+}
+
+// Point du segment [a, b] le plus proche de `p`
+pub fn project_on_segment(p: Point, a: Point, b: Point) -> Point {
+    let l2 = a.distance_sq(b);
+    if l2 == 0.0 { return a; }
+    let t = ((p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y)) / l2;
+    a + (b - a) * t.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{point, vec2};
+
+    fn is_orthogonal(path: &[Point]) -> bool {
+        path.windows(2).all(|s| s[0].x == s[1].x || s[0].y == s[1].y)
+    }
+
+    // Une boîte entre les deux extrémités est contournée à angles droits
+    #[test]
+    fn route_avoids_boxes() {
+        let source = Rect::from_min_size(point(0.0, 0.0), vec2(100.0, 60.0));
+        let obstacle = Rect::from_min_size(point(200.0, -20.0), vec2(100.0, 100.0));
+        let target = Rect::from_min_size(point(400.0, 0.0), vec2(100.0, 60.0));
+        let start = RouteEnd { pos: point(source.right(), 30.0), dir: side_normal(Side::Right) };
+        let end = RouteEnd { pos: point(target.left(), 30.0), dir: -side_normal(Side::Left) };
+        let path = route(start, end, &[], &[source, obstacle, target]);
+
+        assert_eq!(path.first(), Some(&start.pos));
+        assert_eq!(path.last(), Some(&end.pos));
+        assert!(is_orthogonal(&path), "{:?}", path);
+        assert!(path.windows(2).all(|s| !Rect::from_two_pos(s[0], s[1]).intersects(obstacle)), "{:?}", path);
+    }
+
+    // Les points de passage sont reliés par des segments horizontaux et verticaux
+    #[test]
+    fn waypoints_are_orthogonalized() {
+        let start = RouteEnd { pos: point(0.0, 0.0), dir: side_normal(Side::Right) };
+        let end = RouteEnd { pos: point(300.0, 200.0), dir: -side_normal(Side::Top) };
+        let path = route(start, end, &[point(150.0, 100.0)], &[]);
+        assert!(is_orthogonal(&path), "{:?}", path);
+        assert!(distance_sq_to_path(point(150.0, 100.0), &path) < 1e-3, "{:?}", path);
+    }
+
+    #[test]
+    fn midpoint_lies_halfway_along_path() {
+        let path = [point(0.0, 0.0), point(100.0, 0.0), point(100.0, 100.0)];
+        assert_eq!(path_midpoint(&path), point(100.0, 0.0));
+        assert_eq!(closest_segment(point(110.0, 80.0), &path), Some(1));
+    }
+}
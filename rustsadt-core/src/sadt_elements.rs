@@ -0,0 +1,242 @@
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+// Alias pour clarté
+pub type NodeId = Uuid;
+pub type ArrowId = Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ArrowType {
+    Input,    // Donnée entrant dans l'activité
+    Output,   // Donnée sortant de l'activité
+    Control,  // Contrainte ou règle guidant l'activité
+    Mechanism, // Ressource utilisée par l'activité (humain, machine)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Side {
+    pub const ALL: [Side; 4] = [Side::Left, Side::Top, Side::Bottom, Side::Right];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Side::Top => "Haut",
+            Side::Bottom => "Bas",
+            Side::Left => "Gauche",
+            Side::Right => "Droite",
+        }
+    }
+
+    // Rôle ICOM d'une flèche attachée à ce côté d'une boîte (convention IDEF0)
+    pub fn icom_role(&self) -> ArrowType {
+        match self {
+            Side::Left => ArrowType::Input,
+            Side::Right => ArrowType::Output,
+            Side::Top => ArrowType::Control,
+            Side::Bottom => ArrowType::Mechanism,
+        }
+    }
+}
+
+// Représente un point de connexion sur un côté d'un nœud
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConnectionPoint {
+    pub node_id: NodeId,
+    pub side: Side,
+    // Emplacement sur le côté: les flèches d'un même côté sont réparties régulièrement
+    // dans l'ordre croissant (de haut en bas, de gauche à droite), qui est aussi celui
+    // de leurs codes ICOM. Attribué par `SadtDiagram::add_arrow`.
+    #[serde(default)]
+    pub slot: u32,
+}
+
+impl ConnectionPoint {
+    pub fn new(node_id: NodeId, side: Side) -> Self {
+        Self { node_id, side, slot: 0 }
+    }
+}
+
+impl ArrowType {
+    pub const ALL: [ArrowType; 4] = [ArrowType::Input, ArrowType::Control, ArrowType::Mechanism, ArrowType::Output];
+
+    // Nom du rôle ICOM
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArrowType::Input => "Entrée",
+            ArrowType::Output => "Sortie",
+            ArrowType::Control => "Contrôle",
+            ArrowType::Mechanism => "Mécanisme",
+        }
+    }
+
+    // Côté de la boîte où ce type de flèche est attaché selon la convention SADT
+    pub fn attachment_side(&self) -> Side {
+        match self {
+            ArrowType::Input => Side::Left,
+            ArrowType::Output => Side::Right,
+            ArrowType::Control => Side::Top,
+            ArrowType::Mechanism => Side::Bottom,
+        }
+    }
+
+    pub fn icom_prefix(&self) -> char {
+        match self {
+            ArrowType::Input => 'I',
+            ArrowType::Output => 'O',
+            ArrowType::Control => 'C',
+            ArrowType::Mechanism => 'M',
+        }
+    }
+
+    // Rang dans l'ordre ICOM (entrées, contrôles, mécanismes, sorties)
+    pub fn icom_rank(&self) -> u8 {
+        match self {
+            ArrowType::Input => 0,
+            ArrowType::Control => 1,
+            ArrowType::Mechanism => 2,
+            ArrowType::Output => 3,
+        }
+    }
+}
+
+// Code ICOM d'une flèche frontière d'un sous-diagramme (I1, C2, O1, M1...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IcomCode {
+    pub arrow_type: ArrowType,
+    pub index: u32, // Commence à 1
+}
+
+impl IcomCode {
+    pub fn new(arrow_type: ArrowType, index: u32) -> Self {
+        Self { arrow_type, index }
+    }
+
+    // Côté du cadre du diagramme où se trouve l'extrémité ouverte
+    pub fn side(&self) -> Side {
+        self.arrow_type.attachment_side()
+    }
+}
+
+impl std::fmt::Display for IcomCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.arrow_type.icom_prefix(), self.index)
+    }
+}
+
+// Extrémité d'une flèche: un point de connexion sur un nœud, une extrémité
+// ouverte sur le bord du diagramme (flèche frontière identifiée par son code ICOM),
+// ou une jonction sur le tracé d'une autre flèche. En source, la flèche est un
+// embranchement qui transporte la même donnée; en cible, elle rejoint le faisceau.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub enum ArrowEnd {
+    Node(ConnectionPoint),
+    Boundary(IcomCode),
+    Junction(ArrowId),
+}
+
+impl ArrowEnd {
+    pub fn node_id(&self) -> Option<NodeId> {
+        self.connection_point().map(|cp| cp.node_id)
+    }
+
+    pub fn connection_point(&self) -> Option<&ConnectionPoint> {
+        match self {
+            ArrowEnd::Node(cp) => Some(cp),
+            _ => None,
+        }
+    }
+
+    pub fn icom(&self) -> Option<IcomCode> {
+        match self {
+            ArrowEnd::Boundary(code) => Some(*code),
+            _ => None,
+        }
+    }
+
+    // Flèche sur laquelle cette extrémité est embranchée
+    pub fn junction(&self) -> Option<ArrowId> {
+        match self {
+            ArrowEnd::Junction(id) => Some(*id),
+            _ => None,
+        }
+    }
+
+    pub fn is_node(&self, id: NodeId) -> bool {
+        self.node_id() == Some(id)
+    }
+}
+
+impl From<ConnectionPoint> for ArrowEnd {
+    fn from(cp: ConnectionPoint) -> Self {
+        ArrowEnd::Node(cp)
+    }
+}
+
+// Lecture d'une extrémité enregistrée: la forme actuelle (`Node(..)`, `Boundary(..)`,
+// `Junction(..)`), ou le simple point de connexion des fichiers antérieurs aux flèches
+// frontières, où l'UUID nul désigne l'extérieur du diagramme. RON ne transmet le nom
+// d'une variante qu'à `deserialize_enum`, qui échoue sur l'ancienne forme: l'extrémité
+// est reconnue à son contenu (le nom est lu quand le format le donne, comme JSON).
+impl<'de> Deserialize<'de> for ArrowEnd {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ArrowEndVisitor)
+    }
+}
+
+struct ArrowEndVisitor;
+
+impl<'de> Visitor<'de> for ArrowEndVisitor {
+    type Value = ArrowEnd;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("une extrémité de flèche (Node, Boundary ou Junction)")
+    }
+
+    // Identifiant de la flèche d'une jonction
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<ArrowEnd, E> {
+        Uuid::parse_str(value).map(ArrowEnd::Junction).map_err(E::custom)
+    }
+
+    // Variante RON `Node((..))`, lue comme un tuple d'un élément
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ArrowEnd, A::Error> {
+        seq.next_element::<ArrowEnd>()?.ok_or_else(|| de::Error::invalid_length(0, &self))
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<ArrowEnd, D::Error> {
+        ArrowEnd::deserialize(deserializer)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ArrowEnd, A::Error> {
+        let (mut node_id, mut side, mut slot): (Option<NodeId>, Option<Side>, u32) = (None, None, 0);
+        let (mut arrow_type, mut index): (Option<ArrowType>, Option<u32>) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "Node" => return Ok(ArrowEnd::Node(map.next_value()?)),
+                "Boundary" => return Ok(ArrowEnd::Boundary(map.next_value()?)),
+                "Junction" => return Ok(ArrowEnd::Junction(map.next_value()?)),
+                "node_id" => node_id = Some(map.next_value()?),
+                "side" => side = Some(map.next_value()?),
+                "slot" => slot = map.next_value()?,
+                "arrow_type" => arrow_type = Some(map.next_value()?),
+                "index" => index = Some(map.next_value()?),
+                _ => { map.next_value::<IgnoredAny>()?; }
+            }
+        }
+        match (node_id, side, arrow_type, index) {
+            // Extrémité ouverte sans numéro (index 0), attribué au chargement
+            // par `SadtDiagram::number_legacy_boundaries`
+            (Some(node_id), Some(side), _, _) if node_id.is_nil() => Ok(ArrowEnd::Boundary(IcomCode::new(side.icom_role(), 0))),
+            (Some(node_id), Some(side), _, _) => Ok(ArrowEnd::Node(ConnectionPoint { node_id, side, slot })),
+            (_, _, Some(arrow_type), Some(index)) => Ok(ArrowEnd::Boundary(IcomCode::new(arrow_type, index))),
+            _ => Err(de::Error::custom("extrémité de flèche incomplète")),
+        }
+    }
+}
@@ -0,0 +1,667 @@
+use crate::data_dictionary::DataDictionary;
+use crate::numbering::next_free_number;
+use crate::geometry::{vec2, Point, Rect, Vec2};
+use crate::sadt_elements::{ArrowEnd, ArrowId, ArrowType, ConnectionPoint, IcomCode, NodeId, Side};
+use crate::routing::{closest_segment, junction_point, path_midpoint, project_on_segment, route, side_normal, RouteEnd};
+use crate::style::{text_width, wrap_text, Color, LINE_HEIGHT, NODE_FONT_SIZE, NODE_TEXT_PADDING};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+pub const DEFAULT_NODE_SIZE: Vec2 = vec2(120.0, 60.0);
+pub const MIN_NODE_SIZE: Vec2 = vec2(40.0, 30.0); // Taille minimale d'un nœud redimensionné
+const MAX_FIT_WIDTH: f32 = 240.0; // Largeur au-delà de laquelle l'ajustement au nom passe à la ligne
+pub const FRAME_MARGIN: f32 = 60.0; // Marge monde entre les nœuds et le cadre du diagramme
+pub const DEFAULT_FRAME_SIZE: Vec2 = vec2(800.0, 500.0); // Cadre d'un diagramme vide
+const MAX_JUNCTION_DEPTH: usize = 32; // Garde-fou contre des jonctions en boucle (fichier modifié à la main)
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProcessNode {
+    pub id: NodeId,
+    pub name: String,
+    pub rect: Rect, // Position et taille en coordonnées monde
+    // Algorithme du registre exécuté par l'activité (vide: aucun, le code généré reste à écrire)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub algorithm: String,
+    // Description libre de l'activité (documentation)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    // Numéro de boîte fixé (à la main ou par une renumérotation); aucun: numéro
+    // automatique dans l'ordre de dominance (voir `numbering`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<u32>,
+    // Couleur de remplissage de la boîte (aucune: couleur du thème)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill: Option<Color>,
+    // Sous-diagramme détaillant cette activité (décomposition A0 -> A1, A2, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub child_diagram: Option<SadtDiagram>,
+    // On pourrait stocker les IDs des flèches connectées ici, mais
+    // il est souvent plus simple de les retrouver via le diagramme global.
+}
+
+impl ProcessNode {
+    pub fn is_decomposed(&self) -> bool {
+        self.child_diagram.is_some()
+    }
+
+    // Position monde du point de connexion d'un côté (milieu du côté), d'où partent les nouvelles flèches
+    pub fn connection_pos(&self, side: Side) -> Point {
+        self.side_pos(side, 0.5)
+    }
+
+    // Position monde à la fraction `t` d'un côté (de haut en bas, de gauche à droite)
+    pub fn side_pos(&self, side: Side, t: f32) -> Point {
+        match side {
+            Side::Left => Point::new(self.rect.left(), self.rect.top() + self.rect.height() * t),
+            Side::Right => Point::new(self.rect.right(), self.rect.top() + self.rect.height() * t),
+            Side::Top => Point::new(self.rect.left() + self.rect.width() * t, self.rect.top()),
+            Side::Bottom => Point::new(self.rect.left() + self.rect.width() * t, self.rect.bottom()),
+        }
+    }
+
+    // Nom découpé en lignes à la largeur de la boîte
+    pub fn label_lines(&self) -> Vec<String> {
+        wrap_text(&self.name, NODE_FONT_SIZE, self.rect.width() - 2.0 * NODE_TEXT_PADDING)
+    }
+
+    // Plus petite taille, pour une largeur voulue, où le nom tient sans déborder
+    pub fn min_size(&self, width: f32) -> Vec2 {
+        let longest_word = self.name.split_whitespace().map(|word| text_width(word, NODE_FONT_SIZE)).fold(0.0, f32::max);
+        let width = width.max(longest_word + 2.0 * NODE_TEXT_PADDING).max(MIN_NODE_SIZE.x);
+        let lines = wrap_text(&self.name, NODE_FONT_SIZE, width - 2.0 * NODE_TEXT_PADDING).len().max(1);
+        let height = lines as f32 * NODE_FONT_SIZE * LINE_HEIGHT + 2.0 * NODE_TEXT_PADDING;
+        vec2(width, height.max(MIN_NODE_SIZE.y))
+    }
+
+    // Taille ajustée au nom: au moins la taille par défaut, passage à la ligne au-delà de `MAX_FIT_WIDTH`
+    pub fn fit_size(&self) -> Vec2 {
+        let one_line = text_width(&self.name, NODE_FONT_SIZE) + 2.0 * NODE_TEXT_PADDING;
+        let size = self.min_size(one_line.clamp(DEFAULT_NODE_SIZE.x, MAX_FIT_WIDTH));
+        vec2(size.x, size.y.max(DEFAULT_NODE_SIZE.y))
+    }
+
+    // Agrandit la boîte si le nom n'y tient plus (le coin supérieur gauche ne bouge pas)
+    pub fn grow_to_label(&mut self) {
+        let min = self.min_size(self.rect.width());
+        let size = vec2(self.rect.width().max(min.x), self.rect.height().max(min.y));
+        self.rect = Rect::from_min_size(self.rect.min, size);
+    }
+
+    // Fraction du côté la plus proche d'une position (inverse de `side_pos`)
+    pub fn side_fraction(&self, side: Side, pos: Point) -> f32 {
+        let t = match side {
+            Side::Left | Side::Right => (pos.y - self.rect.top()) / self.rect.height(),
+            Side::Top | Side::Bottom => (pos.x - self.rect.left()) / self.rect.width(),
+        };
+        t.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Arrow {
+    pub id: ArrowId,
+    pub label: Option<String>,
+    pub arrow_type: ArrowType,
+    pub source: ArrowEnd,
+    pub target: ArrowEnd,
+    // Type de la donnée transportée, nom d'un type du dictionnaire de données
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    // Points de passage imposés par l'utilisateur (vide: routage automatique)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waypoints: Vec<Point>,
+}
+
+impl Arrow {
+    // Code ICOM si la flèche est une flèche frontière (extrémité ouverte)
+    pub fn icom(&self) -> Option<IcomCode> {
+        self.source.icom().or_else(|| self.target.icom())
+    }
+
+    pub fn touches_node(&self, id: NodeId) -> bool {
+        self.source.is_node(id) || self.target.is_node(id)
+    }
+
+    // Flèche embranchée ou rejointe (extrémité sur le tracé de `id`)
+    pub fn touches_arrow(&self, id: ArrowId) -> bool {
+        self.source.junction() == Some(id) || self.target.junction() == Some(id)
+    }
+
+    // Rôle de la donnée pour l'activité qui la reçoit. Une flèche entre deux activités
+    // tracée côté gauche est de type Output (pour la source) mais reste une entrée (pour la cible).
+    pub fn consumed_role(&self) -> ArrowType {
+        match (&self.target, self.arrow_type) {
+            (_, ArrowType::Control) => ArrowType::Control,
+            (_, ArrowType::Mechanism) => ArrowType::Mechanism,
+            (target, _) if target.icom().is_some() => ArrowType::Output,
+            _ => ArrowType::Input,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SadtDiagram {
+    pub nodes: HashMap<NodeId, ProcessNode>,
+    pub arrows: HashMap<ArrowId, Arrow>,
+    // Types référencés par les flèches. Seul celui du diagramme racine est utilisé:
+    // les sous-diagrammes partagent le dictionnaire de la racine.
+    #[serde(default, skip_serializing_if = "DataDictionary::is_empty")]
+    pub data_dictionary: DataDictionary,
+    #[serde(default, skip_serializing_if = "DiagramMetadata::is_empty")]
+    pub metadata: DiagramMetadata,
+}
+
+// Informations générales d'un diagramme, reprises dans le cartouche IDEF0
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DiagramMetadata {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    // Référence du nœud (A-0, A0, A12...); vide: déduite de la place dans la hiérarchie
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub node_reference: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub project: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub date: String, // Texte libre (ex: 2024-05-17)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub revision: String,
+    #[serde(default)]
+    pub status: DiagramStatus,
+    // Numéro C: identifiant chronologique de la feuille (initiales de l'auteur et numéro)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub c_number: String,
+}
+
+// Étape de validation d'un diagramme (cases du cartouche IDEF0)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DiagramStatus {
+    #[default]
+    Working,
+    Draft,
+    Recommended,
+    Publication,
+}
+
+impl DiagramStatus {
+    pub const ALL: [DiagramStatus; 4] = [DiagramStatus::Working, DiagramStatus::Draft, DiagramStatus::Recommended, DiagramStatus::Publication];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DiagramStatus::Working => "En cours",
+            DiagramStatus::Draft => "Ébauche",
+            DiagramStatus::Recommended => "Recommandé",
+            DiagramStatus::Publication => "Publication",
+        }
+    }
+}
+
+impl DiagramMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == DiagramMetadata::default()
+    }
+}
+
+impl SadtDiagram {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // La nouvelle boîte prend le plus petit numéro libre, qu'elle garde ensuite
+    pub fn add_node(&mut self, name: String, pos: Point) -> NodeId {
+        let id = Uuid::new_v4();
+        let node = ProcessNode {
+            id,
+            name,
+            rect: Rect::from_min_size(pos, DEFAULT_NODE_SIZE),
+            algorithm: String::new(),
+            description: String::new(),
+            number: Some(next_free_number(self)),
+            fill: None,
+            child_diagram: None,
+        };
+        self.nodes.insert(id, node);
+        id
+    }
+
+    pub fn add_arrow(
+        &mut self,
+        source: impl Into<ArrowEnd>,
+        target: impl Into<ArrowEnd>,
+        arrow_type: ArrowType,
+        label: Option<String>,
+    ) -> Option<ArrowId> {
+        let source = source.into();
+        let target = target.into();
+
+        // Vérifier que les noeuds source et target existent
+        for end in [&source, &target] {
+            if let Some(node_id) = end.node_id() {
+                if !self.nodes.contains_key(&node_id) {
+                    log::warn!("Tentative de création de flèche vers/depuis un noeud inexistant.");
+                    return None;
+                }
+            }
+            if let Some(arrow_id) = end.junction() {
+                if !self.arrows.contains_key(&arrow_id) {
+                    log::warn!("Tentative d'embranchement sur une flèche inexistante.");
+                    return None;
+                }
+            }
+        }
+        if source.junction().is_some() && target.junction().is_some() {
+            log::warn!("Une flèche ne peut pas relier deux jonctions.");
+            return None;
+        }
+
+        // Flèches frontières: une seule extrémité ouverte, cohérente avec le code ICOM
+        match (source.icom(), target.icom()) {
+            (Some(_), Some(_)) => {
+                log::warn!("Une flèche ne peut pas avoir ses deux extrémités sur le bord du diagramme.");
+                return None;
+            }
+            (Some(code), None) if code.arrow_type == ArrowType::Output || code.arrow_type != arrow_type => {
+                log::warn!("Code ICOM {} invalide pour une flèche frontière entrante de type {:?}.", code, arrow_type);
+                return None;
+            }
+            (None, Some(code)) if code.arrow_type != ArrowType::Output || arrow_type != ArrowType::Output => {
+                log::warn!("Code ICOM {} invalide pour une flèche frontière sortante.", code);
+                return None;
+            }
+            _ => {}
+        }
+
+        // Les règles SADT (Input à gauche, Control en haut, etc.) ne bloquent pas la création:
+        // elles sont signalées par le module `validation`.
+
+        // La nouvelle flèche prend le dernier emplacement de son côté
+        let (mut source, mut target) = (source, target);
+        for end in [&mut source, &mut target] {
+            if let ArrowEnd::Node(cp) = end {
+                cp.slot = self.next_slot(cp.node_id, cp.side);
+            }
+        }
+
+        let id = Uuid::new_v4();
+        let arrow = Arrow {
+            id,
+            label,
+            arrow_type,
+            source,
+            target,
+            data_type: None,
+            waypoints: Vec::new(),
+        };
+        self.arrows.insert(id, arrow);
+        Some(id)
+    }
+
+    pub fn get_node(&self, id: NodeId) -> Option<&ProcessNode> {
+        self.nodes.get(&id)
+    }
+
+     pub fn get_node_mut(&mut self, id: NodeId) -> Option<&mut ProcessNode> {
+        self.nodes.get_mut(&id)
+    }
+
+    pub fn get_arrow(&self, id: ArrowId) -> Option<&Arrow> {
+        self.arrows.get(&id)
+    }
+
+    pub fn remove_node(&mut self, id: NodeId) -> Option<ProcessNode> {
+        // Supprimer aussi les flèches connectées
+        self.arrows.retain(|_, arrow| !arrow.touches_node(id));
+        self.remove_orphan_junctions();
+        self.nodes.remove(&id)
+    }
+
+    // Supprime aussi les embranchements et jonctions de la flèche
+     pub fn remove_arrow(&mut self, id: ArrowId) -> Option<Arrow> {
+        let removed = self.arrows.remove(&id);
+        self.remove_orphan_junctions();
+        removed
+    }
+
+    // Flèches embranchées sur une flèche supprimée (en cascade)
+    fn remove_orphan_junctions(&mut self) {
+        loop {
+            let orphans: Vec<ArrowId> = self.arrows.values()
+                .filter(|arrow| [&arrow.source, &arrow.target].iter().any(|end| end.junction().is_some_and(|id| !self.arrows.contains_key(&id))))
+                .map(|arrow| arrow.id)
+                .collect();
+            if orphans.is_empty() { break; }
+            for id in orphans { self.arrows.remove(&id); }
+        }
+    }
+
+    // Déplace un groupe de nœuds; les points de passage des flèches reliant deux
+    // nœuds du groupe suivent, les autres tracés restent en place
+    pub fn translate_nodes(&mut self, ids: &[NodeId], delta: Vec2) {
+        for id in ids {
+            if let Some(node) = self.nodes.get_mut(id) { node.rect = node.rect.translate(delta); }
+        }
+        let moved = |end: &ArrowEnd| end.node_id().is_some_and(|id| ids.contains(&id));
+        for arrow in self.arrows.values_mut() {
+            if moved(&arrow.source) && moved(&arrow.target) {
+                for waypoint in &mut arrow.waypoints { *waypoint += delta; }
+            }
+        }
+    }
+
+    // Entrées (hors contrôles et mécanismes) d'une activité, triées par label
+    // (ordre des paramètres de la fonction générée). Une flèche qui rejoint un
+    // faisceau est une entrée de l'activité où arrive le faisceau.
+    pub fn node_inputs(&self, id: NodeId) -> Vec<&Arrow> {
+        self.sorted_arrows(|arrow| {
+            let delivered = self.delivered(arrow);
+            delivered.target.is_node(id) && delivered.consumed_role() == ArrowType::Input
+        })
+    }
+
+    // Sorties d'une activité, triées par label
+    pub fn node_outputs(&self, id: NodeId) -> Vec<&Arrow> {
+        self.sorted_arrows(|arrow| arrow.source.is_node(id))
+    }
+
+    // Flèche qui produit la donnée transportée: en remontant les embranchements,
+    // celle qui part d'une activité ou du cadre
+    pub fn origin<'a>(&'a self, arrow: &'a Arrow) -> &'a Arrow {
+        let mut origin = arrow;
+        for _ in 0..MAX_JUNCTION_DEPTH {
+            match origin.source.junction().and_then(|id| self.get_arrow(id)) {
+                Some(trunk) => origin = trunk,
+                None => break,
+            }
+        }
+        origin
+    }
+
+    // Flèche qui livre la donnée: en suivant les jonctions, celle qui arrive sur une
+    // activité ou au cadre (le faisceau dont la flèche fait partie)
+    pub fn delivered<'a>(&'a self, arrow: &'a Arrow) -> &'a Arrow {
+        let mut delivered = arrow;
+        for _ in 0..MAX_JUNCTION_DEPTH {
+            match delivered.target.junction().and_then(|id| self.get_arrow(id)) {
+                Some(bundle) => delivered = bundle,
+                None => break,
+            }
+        }
+        delivered
+    }
+
+    // Embranchements d'une flèche (et leurs propres embranchements): ils transportent
+    // la même donnée
+    pub fn branches(&self, id: ArrowId) -> Vec<ArrowId> {
+        let mut branches = Vec::new();
+        let mut pending = vec![id];
+        while let Some(trunk) = pending.pop() {
+            for arrow in self.arrows.values().filter(|arrow| arrow.source.junction() == Some(trunk)) {
+                if branches.contains(&arrow.id) || arrow.id == id { continue; }
+                branches.push(arrow.id);
+                pending.push(arrow.id);
+            }
+        }
+        branches
+    }
+
+    fn sorted_arrows(&self, filter: impl Fn(&Arrow) -> bool) -> Vec<&Arrow> {
+        let mut arrows: Vec<&Arrow> = self.arrows.values().filter(|arrow| filter(arrow)).collect();
+        arrows.sort_by(|a, b| a.label.cmp(&b.label).then(a.id.cmp(&b.id)));
+        arrows
+    }
+
+    // Flèches frontières du diagramme (extrémité ouverte sur le cadre)
+    pub fn boundary_arrows(&self) -> impl Iterator<Item = &Arrow> {
+        self.arrows.values().filter(|arrow| arrow.icom().is_some())
+    }
+
+    // Numérote les extrémités ouvertes des fichiers antérieurs aux flèches frontières
+    // (code ICOM d'index 0): entrée, contrôle ou mécanisme selon le type de la flèche
+    // côté source, sortie côté cible, au numéro suivant de ce type. Les sous-diagrammes aussi.
+    pub fn number_legacy_boundaries(&mut self) {
+        let unnumbered: Vec<ArrowId> = self.sorted_arrows(|arrow| arrow.icom().is_some_and(|code| code.index == 0))
+            .into_iter().map(|arrow| arrow.id).collect();
+        for id in unnumbered {
+            let arrow = &self.arrows[&id];
+            let arrow_type = match arrow.arrow_type {
+                _ if arrow.target.icom().is_some() => ArrowType::Output,
+                ArrowType::Output => ArrowType::Input,
+                arrow_type => arrow_type,
+            };
+            let index = self.boundary_arrows().filter_map(Arrow::icom)
+                .filter(|code| code.arrow_type == arrow_type)
+                .map(|code| code.index)
+                .max().unwrap_or(0) + 1;
+            let Some(arrow) = self.arrows.get_mut(&id) else { continue };
+            let end = if arrow.target.icom().is_some() { &mut arrow.target } else { &mut arrow.source };
+            *end = ArrowEnd::Boundary(IcomCode::new(arrow_type, index));
+        }
+        for node in self.nodes.values_mut() {
+            if let Some(child) = node.child_diagram.as_mut() {
+                child.number_legacy_boundaries();
+            }
+        }
+    }
+
+    // Cadre du diagramme: englobe les nœuds avec une marge. Les extrémités
+    // ouvertes des flèches frontières s'appuient dessus.
+    pub fn frame(&self) -> Rect {
+        let content = self.nodes.values().fold(Rect::NOTHING, |acc, node| acc.union(node.rect));
+        if content.is_negative() {
+            return Rect::from_min_size(Point::ZERO, DEFAULT_FRAME_SIZE);
+        }
+        content.expand(FRAME_MARGIN)
+    }
+
+    // Flèches attachées à un côté d'une boîte, dans l'ordre de leurs emplacements.
+    // À emplacement égal (fichiers antérieurs aux emplacements), l'ordre suit la position
+    // de l'autre extrémité, les flèches frontières en premier dans l'ordre de leurs codes.
+    pub fn side_arrows(&self, node_id: NodeId, side: Side) -> Vec<ArrowId> {
+        let mut attached = Vec::new();
+        for arrow in self.arrows.values() {
+            for (own, other) in [(&arrow.source, &arrow.target), (&arrow.target, &arrow.source)] {
+                let Some(cp) = own.connection_point().filter(|cp| cp.node_id == node_id && cp.side == side) else { continue };
+                let other_pos = other.node_id().and_then(|id| self.get_node(id)).map(|node| match side {
+                    Side::Left | Side::Right => node.rect.center().y,
+                    Side::Top | Side::Bottom => node.rect.center().x,
+                });
+                let icom_index = other.icom().map_or(0, |code| code.index);
+                attached.push((cp.slot, other_pos.unwrap_or(f32::NEG_INFINITY), icom_index, arrow.id));
+            }
+        }
+        attached.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)).then(a.2.cmp(&b.2)).then(a.3.cmp(&b.3)));
+        attached.into_iter().map(|(_, _, _, id)| id).collect()
+    }
+
+    fn next_slot(&self, node_id: NodeId, side: Side) -> u32 {
+        self.arrows.values()
+            .flat_map(|arrow| [&arrow.source, &arrow.target])
+            .filter_map(ArrowEnd::connection_point)
+            .filter(|cp| cp.node_id == node_id && cp.side == side)
+            .map(|cp| cp.slot + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Position monde d'une extrémité de flèche sur une boîte: les flèches d'un même
+    // côté sont réparties régulièrement dans l'ordre de leurs emplacements
+    pub fn slot_pos(&self, cp: &ConnectionPoint, arrow_id: ArrowId) -> Option<Point> {
+        let node = self.get_node(cp.node_id)?;
+        let arrows = self.side_arrows(cp.node_id, cp.side);
+        let rank = arrows.iter().position(|id| *id == arrow_id).unwrap_or(0);
+        Some(node.side_pos(cp.side, (rank + 1) as f32 / (arrows.len().max(1) + 1) as f32))
+    }
+
+    // Rang sur son côté le plus proche d'une position (pour réordonner à la souris)
+    pub fn slot_rank_at(&self, node_id: NodeId, side: Side, pos: Point) -> Option<usize> {
+        let node = self.get_node(node_id)?;
+        let count = self.side_arrows(node_id, side).len();
+        let rank = (node.side_fraction(side, pos) * (count + 1) as f32).round() as usize;
+        Some(rank.clamp(1, count.max(1)) - 1)
+    }
+
+    // Place l'extrémité d'une flèche (la source si `source`, sinon la cible) au rang
+    // `rank` de son côté; les emplacements du côté sont renumérotés
+    pub fn move_to_slot(&mut self, arrow_id: ArrowId, source: bool, rank: usize) {
+        let Some(arrow) = self.get_arrow(arrow_id) else { return };
+        let end = if source { &arrow.source } else { &arrow.target };
+        let Some(ConnectionPoint { node_id, side, .. }) = end.connection_point().cloned() else { return };
+        let mut order = self.side_arrows(node_id, side);
+        order.retain(|id| *id != arrow_id);
+        order.insert(rank.min(order.len()), arrow_id);
+        for (slot, id) in order.into_iter().enumerate() {
+            let Some(arrow) = self.arrows.get_mut(&id) else { continue };
+            for end in [&mut arrow.source, &mut arrow.target] {
+                if let ArrowEnd::Node(cp) = end {
+                    if cp.node_id == node_id && cp.side == side { cp.slot = slot as u32; }
+                }
+            }
+        }
+    }
+
+    // Rattache l'extrémité d'une flèche (la source si `source`, sinon la cible) à un autre
+    // côté de sa boîte, au dernier emplacement de ce côté
+    pub fn set_end_side(&mut self, arrow_id: ArrowId, source: bool, side: Side) {
+        let Some(arrow) = self.get_arrow(arrow_id) else { return };
+        let end = if source { &arrow.source } else { &arrow.target };
+        let Some(cp) = end.connection_point() else { return };
+        if cp.side == side { return; }
+        let slot = self.next_slot(cp.node_id, side);
+        let Some(arrow) = self.arrows.get_mut(&arrow_id) else { return };
+        let end = if source { &mut arrow.source } else { &mut arrow.target };
+        if let ArrowEnd::Node(cp) = end {
+            cp.side = side;
+            cp.slot = slot;
+        }
+    }
+
+    // Positions monde des deux extrémités d'une flèche (nœuds, bord du cadre ou jonctions)
+    pub fn arrow_endpoints(&self, arrow: &Arrow) -> Option<(Point, Point)> {
+        self.route_ends(arrow, 0).map(|(start, end)| (start.pos, end.pos))
+    }
+
+    // Tracé orthogonal d'une flèche, de la source à la cible (voir `routing`): c'est lui
+    // qui est dessiné, exporté et utilisé pour la sélection
+    pub fn arrow_path(&self, arrow: &Arrow) -> Option<Vec<Point>> {
+        self.path_at_depth(arrow, 0)
+    }
+
+    // `depth`: nombre de jonctions remontées pour placer celles de la flèche
+    fn path_at_depth(&self, arrow: &Arrow, depth: usize) -> Option<Vec<Point>> {
+        let (start, end) = self.route_ends(arrow, depth)?;
+        let obstacles: Vec<Rect> = self.nodes.values().map(|node| node.rect).collect();
+        Some(route(start, end, &arrow.waypoints, &obstacles))
+    }
+
+    // Extrémités d'un tracé et sens de parcours. Une flèche quitte une boîte par la normale
+    // de son côté et entre dans le diagramme par l'intérieur du cadre; à l'arrivée, c'est
+    // l'inverse. Une jonction se place sur l'autre tracé au plus près du point de passage
+    // voisin (ou de l'autre extrémité), la flèche s'en écartant à angle droit.
+    fn route_ends(&self, arrow: &Arrow, depth: usize) -> Option<(RouteEnd, RouteEnd)> {
+        let frame = self.frame();
+        let obstacles: Vec<Rect> = self.nodes.values().map(|node| node.rect).collect();
+        let node_end = |end: &ArrowEnd| {
+            let cp = end.connection_point()?;
+            Some(RouteEnd { pos: self.slot_pos(cp, arrow.id)?, dir: side_normal(cp.side) })
+        };
+        let source_node = node_end(&arrow.source);
+        let target_node = node_end(&arrow.target).map(|end| RouteEnd { pos: end.pos, dir: -end.dir });
+
+        let junction_end = |trunk_id: ArrowId, waypoint: Option<&Point>, other: &ArrowEnd, other_node: Option<RouteEnd>| {
+            if depth >= MAX_JUNCTION_DEPTH { return None; }
+            let trunk = self.path_at_depth(self.get_arrow(trunk_id)?, depth + 1)?;
+            let toward = match (waypoint, other_node, other) {
+                (Some(waypoint), _, _) => *waypoint,
+                (None, Some(end), _) => end.pos,
+                (None, None, ArrowEnd::Boundary(code)) => project_on_frame(frame, code.side(), path_midpoint(&trunk)),
+                (None, None, _) => path_midpoint(&trunk),
+            };
+            let (pos, dir) = junction_point(&trunk, toward, &obstacles)?;
+            Some(RouteEnd { pos, dir })
+        };
+        let start = match &arrow.source {
+            ArrowEnd::Junction(trunk) => junction_end(*trunk, arrow.waypoints.first(), &arrow.target, target_node),
+            _ => source_node,
+        };
+        let end = match &arrow.target {
+            ArrowEnd::Junction(bundle) => junction_end(*bundle, arrow.waypoints.last(), &arrow.source, source_node)
+                .map(|end| RouteEnd { pos: end.pos, dir: -end.dir }),
+            _ => target_node,
+        };
+
+        // Extrémité ouverte: sur le cadre, en face de l'autre extrémité
+        match (&arrow.source, &arrow.target) {
+            (ArrowEnd::Boundary(code), _) => {
+                let end = end?;
+                Some((RouteEnd { pos: project_on_frame(frame, code.side(), end.pos), dir: -side_normal(code.side()) }, end))
+            }
+            (_, ArrowEnd::Boundary(code)) => {
+                let start = start?;
+                Some((start, RouteEnd { pos: project_on_frame(frame, code.side(), start.pos), dir: side_normal(code.side()) }))
+            }
+            _ => Some((start?, end?)),
+        }
+    }
+
+    // Remplace le routage automatique par des points de passage reproduisant le tracé
+    // courant, que l'utilisateur peut ensuite déplacer
+    pub fn freeze_arrow_path(&mut self, arrow_id: ArrowId) {
+        let Some(path) = self.get_arrow(arrow_id).filter(|arrow| arrow.waypoints.is_empty()).and_then(|arrow| self.arrow_path(arrow)) else { return };
+        if let Some(arrow) = self.arrows.get_mut(&arrow_id) {
+            arrow.waypoints = path[1..path.len() - 1].to_vec();
+        }
+    }
+
+    // Ajoute un point de passage sur le tracé, au plus près de `pos` (le tracé ne change
+    // pas tant que le point n'est pas déplacé); retourne son indice
+    pub fn add_waypoint(&mut self, arrow_id: ArrowId, pos: Point) -> Option<usize> {
+        self.freeze_arrow_path(arrow_id);
+        let arrow = self.get_arrow(arrow_id)?;
+        let (start, end) = self.arrow_endpoints(arrow)?;
+        let mut control = vec![start];
+        control.extend_from_slice(&arrow.waypoints);
+        control.push(end);
+        let index = closest_segment(pos, &control)?;
+        let waypoint = project_on_segment(pos, control[index], control[index + 1]);
+        self.arrows.get_mut(&arrow_id)?.waypoints.insert(index, waypoint);
+        Some(index)
+    }
+
+    // Reporte le renommage (ou la suppression, `new` à None) d'un type du dictionnaire
+    // sur les flèches de ce diagramme et de ses sous-diagrammes
+    pub fn retype_arrows(&mut self, old: &str, new: Option<&str>) {
+        for arrow in self.arrows.values_mut() {
+            if arrow.data_type.as_deref() == Some(old) {
+                arrow.data_type = new.map(str::to_string);
+            }
+        }
+        for node in self.nodes.values_mut() {
+            if let Some(child) = node.child_diagram.as_mut() {
+                child.retype_arrows(old, new);
+            }
+        }
+    }
+
+    // Nœuds décomposés avec leur sous-diagramme, triés par nom pour un ordre stable
+    pub fn decomposed_nodes(&self) -> Vec<(&ProcessNode, &SadtDiagram)> {
+        let mut children: Vec<_> = self.nodes.values()
+            .filter_map(|node| node.child_diagram.as_ref().map(|child| (node, child)))
+            .collect();
+        children.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name).then(a.id.cmp(&b.id)));
+        children
+    }
+}
+
+// Projette un point sur le côté du cadre (les flèches frontières entrent à angle droit)
+fn project_on_frame(frame: Rect, side: Side, pos: Point) -> Point {
+    match side {
+        Side::Left => Point::new(frame.left(), pos.y),
+        Side::Right => Point::new(frame.right(), pos.y),
+        Side::Top => Point::new(pos.x, frame.top()),
+        Side::Bottom => Point::new(pos.x, frame.bottom()),
+    }
+}
@@ -0,0 +1,125 @@
+// rustsadt-core/src/simulation.rs
+// Exécution d'un diagramme: les valeurs saisies sur les flèches entrant par le cadre
+// traversent les activités (dans l'ordre de `pipeline::execution_order`), chacune
+// appliquant son algorithme, jusqu'aux flèches sortant par le cadre.
+use crate::algorithms::find_algorithm;
+use crate::data_dictionary::DataDictionary;
+use crate::error::Result;
+use crate::pipeline::execution_order;
+use crate::sadt_elements::{ArrowId, ArrowType, NodeId};
+use crate::sadt_model::{Arrow, SadtDiagram};
+use crate::validation::{Diagnostic, DiagnosticElement, Severity};
+use std::collections::HashMap;
+
+pub struct Simulation {
+    order: Vec<NodeId>,
+    next: usize, // Index dans `order` de la prochaine activité à exécuter
+    values: HashMap<ArrowId, f64>,
+    errors: Vec<(NodeId, String)>,
+}
+
+impl Simulation {
+    // Prépare l'exécution; `inputs` donne la valeur des flèches entrant par le cadre
+    // (une flèche sans valeur bloque les activités qui en dépendent)
+    pub fn new(diagram: &SadtDiagram, inputs: &HashMap<ArrowId, f64>) -> Result<Self> {
+        let mut simulation = Simulation { order: execution_order(diagram)?, next: 0, values: HashMap::new(), errors: Vec::new() };
+        for arrow in external_inputs(diagram) {
+            if let Some(value) = inputs.get(&arrow.id) {
+                simulation.set_value(diagram, arrow.id, *value);
+            }
+        }
+        Ok(simulation)
+    }
+
+    // La valeur d'une flèche se retrouve sur tous ses embranchements
+    fn set_value(&mut self, diagram: &SadtDiagram, arrow_id: ArrowId, value: f64) {
+        self.values.insert(arrow_id, value);
+        for branch in diagram.branches(arrow_id) {
+            self.values.insert(branch, value);
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.order.len()
+    }
+
+    // Activité exécutée par le prochain pas
+    pub fn next_node(&self) -> Option<NodeId> {
+        self.order.get(self.next).copied()
+    }
+
+    // Valeur portée par une flèche (None tant qu'elle n'a pas été produite)
+    pub fn value(&self, arrow_id: ArrowId) -> Option<f64> {
+        self.values.get(&arrow_id).copied()
+    }
+
+    pub fn values(&self) -> &HashMap<ArrowId, f64> {
+        &self.values
+    }
+
+    // Exécute la prochaine activité et la retourne; une erreur n'interrompt pas la
+    // simulation, mais les activités en aval n'auront pas leurs entrées
+    pub fn step(&mut self, diagram: &SadtDiagram, dictionary: &DataDictionary) -> Option<NodeId> {
+        let node_id = self.next_node()?;
+        self.next += 1;
+        if let Err(message) = self.execute(diagram, dictionary, node_id) {
+            self.errors.push((node_id, message));
+        }
+        Some(node_id)
+    }
+
+    pub fn run(&mut self, diagram: &SadtDiagram, dictionary: &DataDictionary) {
+        while self.step(diagram, dictionary).is_some() {}
+    }
+
+    fn execute(&mut self, diagram: &SadtDiagram, dictionary: &DataDictionary, node_id: NodeId) -> std::result::Result<(), String> {
+        let node = diagram.get_node(node_id).ok_or("Activité introuvable")?;
+        if node.algorithm.is_empty() {
+            return Err(format!("L'activité \"{}\" n'a pas d'algorithme à exécuter", node.name));
+        }
+        let algorithm = find_algorithm(&node.algorithm)
+            .ok_or_else(|| format!("L'activité \"{}\" utilise un algorithme inconnu: \"{}\"", node.name, node.algorithm))?;
+        if let Some(issue) = algorithm.signature_issues(diagram, dictionary, node).into_iter().next() {
+            return Err(issue);
+        }
+
+        let mut args = Vec::new();
+        for arrow in diagram.node_inputs(node_id) {
+            let value = self.value(arrow.id).ok_or_else(|| format!("L'entrée \"{}\" de l'activité \"{}\" n'a pas de valeur",
+                arrow.label.as_deref().unwrap_or("sans label"), node.name))?;
+            args.push(value);
+        }
+        let result = (algorithm.run)(&args).map_err(|e| format!("{} dans l'activité \"{}\" ({})", e, node.name, algorithm.name))?;
+        for arrow in diagram.node_outputs(node_id) {
+            self.set_value(diagram, arrow.id, result);
+        }
+        Ok(())
+    }
+
+    // Erreurs d'exécution, sous forme de diagnostics rattachés aux activités
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.errors.iter().map(|(node_id, message)| Diagnostic {
+            severity: Severity::Error,
+            element: DiagnosticElement::Node(*node_id),
+            path: Vec::new(),
+            message: message.clone(),
+        }).collect()
+    }
+}
+
+// Entrées de données du diagramme (flèches entrant par le cadre vers une entrée
+// d'activité), dans l'ordre de leurs codes ICOM. Contrôles et mécanismes ne portent pas de valeur.
+pub fn external_inputs(diagram: &SadtDiagram) -> Vec<&Arrow> {
+    let mut arrows: Vec<&Arrow> = diagram.arrows.values()
+        .filter(|arrow| arrow.source.icom().is_some() && arrow.consumed_role() == ArrowType::Input)
+        .collect();
+    arrows.sort_by_key(|arrow| arrow.source.icom().map(|code| (code.arrow_type.icom_rank(), code.index)));
+    arrows
+}
+
+// Flèches sortant par le cadre du diagramme, dans l'ordre de leurs codes ICOM
+pub fn external_outputs(diagram: &SadtDiagram) -> Vec<&Arrow> {
+    let mut arrows: Vec<&Arrow> = diagram.arrows.values().filter(|arrow| arrow.target.icom().is_some()).collect();
+    arrows.sort_by_key(|arrow| arrow.target.icom().map(|code| code.index));
+    arrows
+}
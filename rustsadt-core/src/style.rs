@@ -0,0 +1,170 @@
+// rustsadt-core/src/style.rs
+// Apparence des diagrammes, commune au canvas de l'éditeur et aux exports SVG/PNG
+use crate::error::{Result, RustSadtError};
+use crate::sadt_elements::ArrowType;
+use serde::{Deserialize, Serialize};
+
+// Tailles en unités monde (à zoom 1 sur le canvas)
+pub const NODE_CORNER_RADIUS: f32 = 5.0;
+pub const ARROW_HEAD_SIZE: f32 = 10.0; // Taille de base de la tête de flèche (sera scalée)
+pub const ARROW_STROKE_WIDTH: f32 = 1.5;
+pub const NODE_FONT_SIZE: f32 = 14.0; // Taille du nom d'un nœud à zoom 1
+pub const LABEL_FONT_SIZE: f32 = 10.0; // Taille des labels de flèches et codes ICOM à zoom 1
+pub const FRAME_CAPTION_FONT_SIZE: f32 = 8.0; // Intitulés des cases du cartouche IDEF0
+pub const FRAME_VALUE_FONT_SIZE: f32 = 12.0; // Valeurs des cases du cartouche
+pub const ICOM_LABEL_OFFSET: f32 = 6.0; // Écart entre l'extrémité ouverte et son code ICOM
+pub const BOX_NUMBER_INSET: f32 = 5.0; // Retrait du numéro de boîte depuis le coin inférieur droit
+pub const DECOMPOSED_INSET: f32 = 3.0; // Retrait de la bordure intérieure d'un nœud décomposé
+pub const JUNCTION_RADIUS: f32 = 3.0; // Point marquant un embranchement ou une jonction sur un tracé
+pub const NODE_TEXT_PADDING: f32 = 8.0; // Marge entre le nom d'un nœud et sa bordure
+pub const LINE_HEIGHT: f32 = 1.2; // Interligne, en multiple de la taille de police
+const CHAR_WIDTH: f32 = 0.55; // Largeur moyenne d'un caractère, en multiple de la taille de police
+
+// Largeur approchée d'un texte. Le canvas et les exports utilisent la même mesure,
+// pour que les retours à la ligne tombent aux mêmes endroits.
+pub fn text_width(text: &str, font_size: f32) -> f32 {
+    text.chars().count() as f32 * font_size * CHAR_WIDTH
+}
+
+// Découpe un texte en lignes d'au plus `max_width`, aux espaces. Un mot plus long
+// que la largeur reste seul sur sa ligne.
+pub fn wrap_text(text: &str, font_size: f32, max_width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        match lines.last_mut() {
+            Some(line) if text_width(&format!("{} {}", line, word), font_size) <= max_width => {
+                line.push(' ');
+                line.push_str(word);
+            }
+            _ => lines.push(word.to_string()),
+        }
+    }
+    lines
+}
+
+// Couleur RGB opaque
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const GRAY: Color = Color::rgb(160, 160, 160);
+
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Color { r, g, b }
+    }
+
+    pub const fn gray(l: u8) -> Self {
+        Color::rgb(l, l, l)
+    }
+
+    // Noir ou blanc, selon ce qui se lit le mieux sur cette couleur de fond
+    pub fn contrasting_text(self) -> Color {
+        let luminance = 0.299 * self.r as f32 + 0.587 * self.g as f32 + 0.114 * self.b as f32;
+        if luminance > 140.0 { Color::BLACK } else { Color::WHITE }
+    }
+
+    // Notation CSS, utilisable telle quelle en SVG
+    pub fn to_css(self) -> String {
+        format!("rgb({}, {}, {})", self.r, self.g, self.b)
+    }
+}
+
+// Couleurs du canvas (thème sombre)
+pub const CANVAS_BACKGROUND: Color = Color::gray(27);
+pub const NODE_FILL: Color = Color::gray(50);
+pub const NODE_STROKE: Color = Color::GRAY;
+pub const NODE_TEXT: Color = Color::WHITE;
+pub const FRAME_STROKE: Color = Color::gray(90);
+
+// Couleur de base d'une flèche selon son type
+pub const fn arrow_color(arrow_type: ArrowType) -> Color {
+    match arrow_type {
+        ArrowType::Input => Color::rgb(0x90, 0xEE, 0x90),     // Vert clair
+        ArrowType::Output => Color::rgb(0xAD, 0xD8, 0xE6),    // Bleu clair
+        ArrowType::Control => Color::rgb(0xFF, 0x80, 0x80),   // Rouge clair
+        ArrowType::Mechanism => Color::rgb(0xFF, 0xFF, 0xE0), // Jaune clair
+    }
+}
+
+// Thème de couleurs des exports SVG/PNG
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportTheme {
+    #[default]
+    Dark,  // Identique au canvas
+    Light, // Fond blanc, adapté à l'impression
+}
+
+impl ExportTheme {
+    pub const ALL: [ExportTheme; 2] = [ExportTheme::Dark, ExportTheme::Light];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportTheme::Dark => "Sombre",
+            ExportTheme::Light => "Clair",
+        }
+    }
+
+    pub fn background(self) -> Color {
+        match self {
+            ExportTheme::Dark => CANVAS_BACKGROUND,
+            ExportTheme::Light => Color::WHITE,
+        }
+    }
+
+    pub fn node_fill(self) -> Color {
+        match self {
+            ExportTheme::Dark => NODE_FILL,
+            ExportTheme::Light => Color::WHITE,
+        }
+    }
+
+    pub fn node_stroke(self) -> Color {
+        match self {
+            ExportTheme::Dark => NODE_STROKE,
+            ExportTheme::Light => Color::BLACK,
+        }
+    }
+
+    pub fn text(self) -> Color {
+        match self {
+            ExportTheme::Dark => NODE_TEXT,
+            ExportTheme::Light => Color::BLACK,
+        }
+    }
+
+    pub fn frame_stroke(self) -> Color {
+        match self {
+            ExportTheme::Dark => FRAME_STROKE,
+            ExportTheme::Light => Color::GRAY,
+        }
+    }
+
+    // Les couleurs claires du canvas sont illisibles sur du blanc: variantes foncées
+    pub fn arrow_color(self, arrow_type: ArrowType) -> Color {
+        match (self, arrow_type) {
+            (ExportTheme::Dark, _) => arrow_color(arrow_type),
+            (ExportTheme::Light, ArrowType::Input) => Color::rgb(0, 0x64, 0),
+            (ExportTheme::Light, ArrowType::Output) => Color::rgb(0, 0, 0x8B),
+            (ExportTheme::Light, ArrowType::Control) => Color::rgb(0x8B, 0, 0),
+            (ExportTheme::Light, ArrowType::Mechanism) => Color::rgb(160, 120, 0),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportTheme {
+    type Err = RustSadtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "dark" | "sombre" => Ok(ExportTheme::Dark),
+            "light" | "clair" => Ok(ExportTheme::Light),
+            _ => Err(RustSadtError::Export(format!("Thème inconnu: {} (dark ou light)", s))),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+// rustsadt-core/src/validation.rs
+// Vérification des règles SADT/IDEF0. Les règles ne bloquent pas l'édition:
+// elles produisent des diagnostics affichés dans le panneau dédié.
+use crate::algorithms::find_algorithm;
+use crate::balancing::check_balance;
+use crate::data_dictionary::DataDictionary;
+use crate::sadt_elements::{ArrowEnd, ArrowId, ArrowType, NodeId};
+use crate::sadt_model::SadtDiagram;
+
+const MIN_BOXES: usize = 3;
+const MAX_BOXES: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+// Élément concerné par un diagnostic (permet de le sélectionner sur le canvas)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticElement {
+    Diagram,
+    Node(NodeId),
+    Arrow(ArrowId),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub element: DiagnosticElement,
+    pub path: Vec<NodeId>, // Sous-diagramme concerné (vide = diagramme racine)
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "Erreur",
+            Severity::Warning => "Avertissement",
+        };
+        write!(f, "{}: {}", severity, self.message)
+    }
+}
+
+// Valide un diagramme et tous ses sous-diagrammes, y compris l'équilibrage ICOM
+pub fn validate_hierarchy(diagram: &SadtDiagram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let dictionary = &diagram.data_dictionary;
+    for problem in dictionary.problems() {
+        diagnostics.push(Diagnostic { severity: Severity::Error, element: DiagnosticElement::Diagram, path: Vec::new(), message: problem });
+    }
+    validate_level(diagram, dictionary, &mut Vec::new(), &mut diagnostics);
+    diagnostics.sort_by_key(|d| d.severity);
+    diagnostics
+}
+
+fn validate_level(diagram: &SadtDiagram, dictionary: &DataDictionary, path: &mut Vec<NodeId>, out: &mut Vec<Diagnostic>) {
+    out.extend(validate_diagram(diagram).into_iter().map(|mut d| { d.path = path.clone(); d }));
+
+    // Types de données référencés par les flèches (dictionnaire de la racine)
+    let mut arrows: Vec<_> = diagram.arrows.values().collect();
+    arrows.sort_by_key(|a| a.id);
+    for arrow in arrows {
+        if let Some(data_type) = arrow.data_type.as_deref().filter(|name| !dictionary.contains(name)) {
+            out.push(Diagnostic {
+                severity: Severity::Warning,
+                element: DiagnosticElement::Arrow(arrow.id),
+                path: path.clone(),
+                message: format!("{} référence le type \"{}\" absent du dictionnaire de données",
+                    arrow_name(arrow.label.as_deref(), arrow.arrow_type), data_type),
+            });
+        }
+    }
+
+    // Donnée d'un embranchement: clonée pour chaque activité qui la reçoit
+    let mut forked: Vec<_> = diagram.arrows.values()
+        .filter(|arrow| arrow.source.junction().is_some())
+        .map(|arrow| diagram.origin(arrow))
+        .collect();
+    forked.sort_by_key(|origin| origin.id);
+    forked.dedup_by_key(|origin| origin.id);
+    for origin in forked {
+        let Some(data_type) = origin.data_type.as_deref().filter(|name| dictionary.contains(name)) else { continue };
+        if !dictionary.is_clone(data_type) {
+            out.push(Diagnostic {
+                severity: Severity::Warning,
+                element: DiagnosticElement::Arrow(origin.id),
+                path: path.clone(),
+                message: format!("{} se divise mais son type \"{}\" n'est pas reconnu comme Clone: le code généré clone la donnée pour chaque branche",
+                    arrow_name(origin.label.as_deref(), origin.arrow_type), data_type),
+            });
+        }
+    }
+
+    // Algorithmes: nom connu du registre et signature compatible avec les flèches
+    let mut nodes: Vec<_> = diagram.nodes.values().filter(|node| !node.algorithm.is_empty()).collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    for node in nodes {
+        let messages = match find_algorithm(&node.algorithm) {
+            Some(algorithm) => algorithm.signature_issues(diagram, dictionary, node),
+            None => vec![format!("L'activité \"{}\" utilise un algorithme inconnu: \"{}\"", node.name, node.algorithm)],
+        };
+        for message in messages {
+            out.push(Diagnostic { severity: Severity::Error, element: DiagnosticElement::Node(node.id), path: path.clone(), message });
+        }
+    }
+
+    for (node, child) in diagram.decomposed_nodes() {
+        for issue in check_balance(diagram, node.id, child) {
+            out.push(Diagnostic {
+                severity: Severity::Error,
+                element: DiagnosticElement::Node(node.id),
+                path: path.clone(),
+                message: issue.to_string(),
+            });
+        }
+        path.push(node.id);
+        validate_level(child, dictionary, path, out);
+        path.pop();
+    }
+}
+
+// Emplacement lisible d'un diagnostic ("Racine > Activité > Sous-activité")
+pub fn diagnostic_location(root: &SadtDiagram, path: &[NodeId]) -> String {
+    let mut parts = vec!["Racine".to_string()];
+    let mut diagram = Some(root);
+    for node_id in path {
+        let node = diagram.and_then(|d| d.get_node(*node_id));
+        parts.push(node.map(|n| n.name.clone()).unwrap_or_default());
+        diagram = node.and_then(|n| n.child_diagram.as_ref());
+    }
+    parts.join(" > ")
+}
+
+// Valide un seul niveau de diagramme (sans ses sous-diagrammes)
+pub fn validate_diagram(diagram: &SadtDiagram) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut push = |severity, element, message: String| {
+        diagnostics.push(Diagnostic { severity, element, path: Vec::new(), message });
+    };
+
+    // Nombre de boîtes par diagramme
+    let box_count = diagram.nodes.len();
+    if !(MIN_BOXES..=MAX_BOXES).contains(&box_count) {
+        push(Severity::Warning, DiagnosticElement::Diagram,
+            format!("Le diagramme contient {} boîte(s), IDEF0 recommande de {} à {}", box_count, MIN_BOXES, MAX_BOXES));
+    }
+
+    // Numéros de boîte fixés en double (les références A12... deviendraient ambiguës)
+    let mut numbered: Vec<_> = diagram.nodes.values().filter_map(|node| Some((node.number?, node))).collect();
+    numbered.sort_by(|(a, node_a), (b, node_b)| a.cmp(b).then(node_a.name.cmp(&node_b.name)));
+    for pair in numbered.windows(2) {
+        let [(number, first), (other, second)] = pair else { continue };
+        if number == other {
+            push(Severity::Warning, DiagnosticElement::Node(second.id),
+                format!("Les activités \"{}\" et \"{}\" portent le même numéro de boîte {}", first.name, second.name, number));
+        }
+    }
+
+    let mut arrows: Vec<_> = diagram.arrows.values().collect();
+    arrows.sort_by_key(|a| a.id);
+    for arrow in &arrows {
+        let element = DiagnosticElement::Arrow(arrow.id);
+        let name = arrow_name(arrow.label.as_deref(), arrow.arrow_type);
+
+        // Flèches orphelines: extrémité vers un nœud inexistant, ou aucune extrémité sur un nœud
+        let dangling = [&arrow.source, &arrow.target].into_iter()
+            .any(|end| end.node_id().is_some_and(|id| !diagram.nodes.contains_key(&id)));
+        if dangling {
+            push(Severity::Error, element, format!("{} est reliée à un nœud inexistant", name));
+            continue;
+        }
+        let detached = [&arrow.source, &arrow.target].into_iter()
+            .any(|end| end.junction().is_some_and(|id| !diagram.arrows.contains_key(&id)));
+        if detached {
+            push(Severity::Error, element, format!("{} est embranchée sur une flèche inexistante", name));
+            continue;
+        }
+        if arrow.source.junction().is_some() && arrow.target.junction().is_some() {
+            push(Severity::Error, element, format!("{} relie deux jonctions", name));
+            continue;
+        }
+        // Un embranchement part de l'activité qui produit la donnée, une jonction
+        // arrive là où le faisceau arrive
+        let (producer, consumer) = (diagram.origin(arrow).source.node_id(), diagram.delivered(arrow).target.node_id());
+        if producer.is_none() && consumer.is_none() {
+            push(Severity::Error, element, format!("{} n'est reliée à aucune activité", name));
+            continue;
+        }
+
+        // Boucle sur une même activité
+        if producer.is_some() && producer == consumer {
+            push(Severity::Error, element, format!("{} boucle sur une même activité", name));
+        }
+
+        // Côtés d'attachement: I à gauche, C en haut, M en bas (côté cible), O à droite (côté source)
+        let (end, expected) = match arrow.arrow_type {
+            ArrowType::Output => (&arrow.source, ArrowType::Output.attachment_side()),
+            other => (&arrow.target, other.attachment_side()),
+        };
+        if let ArrowEnd::Node(cp) = end {
+            if cp.side != expected {
+                push(Severity::Error, element, format!(
+                    "{} doit être attachée au côté {} de l'activité (actuellement {})", name, expected.label(), cp.side.label()));
+            }
+        }
+
+        // Un embranchement transporte la donnée de sa flèche d'origine (et son type)
+        let origin = diagram.origin(arrow);
+        if origin.id != arrow.id && arrow.data_type.is_some() && arrow.data_type != origin.data_type {
+            push(Severity::Warning, element, format!("{} n'a pas le même type de donnée que la flèche dont elle part", name));
+        }
+    }
+
+    // Chaque activité a au moins un contrôle et une sortie
+    let mut nodes: Vec<_> = diagram.nodes.values().collect();
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    for node in nodes {
+        let roles: Vec<ArrowType> = diagram.arrows.values()
+            .flat_map(|arrow| [&arrow.source, &arrow.target])
+            .filter_map(|end| end.connection_point())
+            .filter(|cp| cp.node_id == node.id)
+            .map(|cp| cp.side.icom_role())
+            .collect();
+        if !roles.contains(&ArrowType::Control) {
+            push(Severity::Error, DiagnosticElement::Node(node.id),
+                format!("L'activité \"{}\" n'a aucun contrôle", node.name));
+        }
+        if !roles.contains(&ArrowType::Output) {
+            push(Severity::Error, DiagnosticElement::Node(node.id),
+                format!("L'activité \"{}\" n'a aucune sortie", node.name));
+        }
+    }
+
+    diagnostics
+}
+
+fn arrow_name(label: Option<&str>, arrow_type: ArrowType) -> String {
+    match label {
+        Some(label) => format!("La flèche \"{}\"", label),
+        None => format!("Une flèche {:?} sans label", arrow_type),
+    }
+}
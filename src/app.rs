@@ -1,20 +1,38 @@
 // src/app.rs
 
 // Imports nécessaires
-use crate::generator::CodeGenerator;
-use crate::persistence::{load_diagram_dialog, save_diagram, save_diagram_dialog};
-use crate::sadt_model::SadtDiagram;
+use rustsadt_core::balancing::parent_icom_codes;
+use rustsadt_core::data_dictionary::DataDictionary;
+use rustsadt_core::frame::{DiagramReference, Idef0Frame};
+use rustsadt_core::generator::CodeGenerator;
+use rustsadt_core::layout::auto_layout;
+use rustsadt_core::numbering::{renumber, renumber_by_dominance};
+use rustsadt_core::protected::MergeReport;
+use crate::history::{History, HistoryEntry};
+use rustsadt_core::validation::{validate_hierarchy, Diagnostic, DiagnosticElement, Severity};
+use crate::dialogs::{load_diagram_dialog, save_diagram_dialog};
+use rustsadt_core::geometry::{Point, Vec2};
+use rustsadt_core::persistence::{save_diagram, PngExportOptions};
+use rustsadt_core::style::ExportTheme;
+use rustsadt_core::sadt_model::SadtDiagram;
+use rustsadt_core::sadt_elements::NodeId;
 use crate::ui::drawing::UiState; // Importe UiState
+use crate::ui::dictionary::{show_dictionary_window, DictionaryEditor};
+use crate::ui::properties::{show_properties_panel, PanelDock, PropertiesPanel};
+use crate::ui::selection::Selection;
+use crate::ui::snapping::GridSettings;
+use crate::ui::simulation::{show_simulation_window, SimulationPanel};
 // Importe les fonctions/types nécessaires pour l'UI et les interactions
-use crate::ui::interaction::handle_canvas_interactions;
+use crate::ui::clipboard::ClipboardState;
+use crate::ui::interaction::{alignment_menu, clipboard_menu, handle_canvas_interactions};
 use eframe::egui;
 use egui::{
-    Vec2, CentralPanel, Frame, Pos2, RichText, Sense, TopBottomPanel,
+    CentralPanel, Color32, Frame, RichText, Sense, TopBottomPanel,
     PointerButton, // Ajout pour Pan
     ViewportCommand,
 };
 use std::path::PathBuf;
-use crate::error::RustSadtError; // Pour show_error_popup
+use rustsadt_core::error::RustSadtError; // Pour show_error_popup
 
 // ------------ Définition de AppState ------------
 pub struct AppState {
@@ -23,13 +41,40 @@ pub struct AppState {
     pub current_file_path: Option<PathBuf>,
     pub code_generator: Option<CodeGenerator>,
     pub generated_code: Option<String>,
+    pub regeneration_report: Option<(PathBuf, MergeReport)>, // Bilan de la dernière régénération dans un fichier
     pub generated_doc: Option<String>,
+    pub show_diagnostics: bool, // Panneau des diagnostics de validation SADT/IDEF0
+    diagnostics: Option<(u64, Vec<Diagnostic>)>, // Diagnostics de la hiérarchie et révision de l'historique validée
+    pub export_theme: ExportTheme, // Thème commun aux exports SVG et PNG
+    pub png_export_options: PngExportOptions,
+    pub show_png_export: bool, // Fenêtre d'options avant l'export PNG
+    pub dictionary_editor: Option<DictionaryEditor>, // Fenêtre du dictionnaire de données ouverte
+    pub simulation: Option<SimulationPanel>, // Fenêtre de simulation ouverte
     pub zoom: f32,           // Niveau de zoom
     pub pan: Vec2,           // Décalage de la vue (en coordonnées monde)
+    // Diagrammes parents mis de côté pendant l'édition d'un sous-diagramme.
+    // `diagram` est toujours le diagramme affiché; la racine est au fond de la pile.
+    pub parent_levels: Vec<ParentLevel>,
+    pub history: History,
+    // Diagramme courant avant une modification continue (drag) pas encore enregistrée
+    pub pending_edit: Option<SadtDiagram>,
+    pub clipboard: ClipboardState, // Fragment copié, conservé d'un diagramme ou d'un fichier à l'autre
+    pub grid: GridSettings, // Grille, magnétisme et guides du canvas
+    pub properties: PropertiesPanel, // Panneau des propriétés de la sélection
+    pub show_form: bool, // Cartouche IDEF0 autour du cadre du canvas
+}
+
+// Niveau parent conservé lors de la navigation vers un sous-diagramme
+pub struct ParentLevel {
+    pub diagram: SadtDiagram,
+    pub node_id: NodeId, // Nœud décomposé dont on édite le sous-diagramme
+    pub zoom: f32,
+    pub pan: Vec2,
 }
 // -------------------------------------------------------
 
 // ------------ Définition de RustSadtApp ------------
+#[derive(Default)]
 pub struct RustSadtApp {
     state: AppState,
 }
@@ -44,19 +89,260 @@ impl Default for AppState { // Implémentation manuelle conservée
             current_file_path: None,
             code_generator: None,
             generated_code: None,
+            regeneration_report: None,
             generated_doc: None,
+            show_diagnostics: false,
+            diagnostics: None,
+            export_theme: ExportTheme::default(),
+            png_export_options: PngExportOptions::default(),
+            show_png_export: false,
+            dictionary_editor: None,
+            simulation: None,
             zoom: 1.0,       // Zoom initial
             pan: Vec2::ZERO, // Pas de décalage initial
+            parent_levels: Vec::new(),
+            history: History::default(),
+            pending_edit: None,
+            clipboard: ClipboardState::default(),
+            grid: GridSettings::default(),
+            properties: PropertiesPanel::default(),
+            show_form: true,
         }
     }
 }
 
-impl Default for RustSadtApp {
-    fn default() -> Self {
-        Self {
-            state: AppState::default(),
+// --- Navigation dans la hiérarchie ---
+impl AppState {
+    // Ouvre le sous-diagramme d'un nœud décomposé
+    pub fn enter_child_diagram(&mut self, node_id: NodeId) -> bool {
+        let Some(node) = self.diagram.get_node_mut(node_id) else {
+            log::warn!("Impossible d'ouvrir le sous-diagramme: nœud {} introuvable", node_id);
+            return false;
+        };
+        // Seul `decompose` crée un sous-diagramme: le replacer au retour ne décompose
+        // jamais un nœud qui ne l'était pas
+        let Some(child) = node.child_diagram.take() else {
+            log::info!("Le nœud {} n'est pas décomposé", node_id);
+            return false;
+        };
+        let parent = std::mem::replace(&mut self.diagram, child);
+        self.parent_levels.push(ParentLevel { diagram: parent, node_id, zoom: self.zoom, pan: self.pan });
+        self.ui_state = UiState::default();
+        self.refresh_boundary_ports();
+        self.zoom = 1.0;
+        self.pan = Vec2::ZERO;
+        log::info!("Entrée dans le sous-diagramme du nœud {}", node_id);
+        true
+    }
+
+    // Décompose un nœud (sous-diagramme vide, annulable) puis ouvre son sous-diagramme
+    pub fn decompose(&mut self, node_id: NodeId) -> bool {
+        self.edit("Décomposer", |diagram| {
+            if let Some(node) = diagram.get_node_mut(node_id) {
+                node.child_diagram.get_or_insert_with(SadtDiagram::new);
+            }
+        });
+        self.enter_child_diagram(node_id)
+    }
+
+    // Revient au diagramme parent en y replaçant le sous-diagramme courant
+    pub fn leave_child_diagram(&mut self) -> bool {
+        let Some(parent) = self.parent_levels.pop() else { return false; };
+        let child = std::mem::replace(&mut self.diagram, parent.diagram);
+        if let Some(node) = self.diagram.get_node_mut(parent.node_id) {
+            node.child_diagram = Some(child);
+        }
+        self.ui_state = UiState::default();
+        self.ui_state.selection = Selection::node(parent.node_id);
+        self.refresh_boundary_ports();
+        self.zoom = parent.zoom;
+        self.pan = parent.pan;
+        log::info!("Retour au diagramme parent");
+        true
+    }
+
+    pub fn leave_to_root(&mut self) {
+        while self.leave_child_diagram() {}
+    }
+
+    // Recalcule les ports ICOM du diagramme courant depuis la boîte parente
+    fn refresh_boundary_ports(&mut self) {
+        self.ui_state.boundary_ports = match self.parent_levels.last() {
+            Some(parent) => parent_icom_codes(&parent.diagram, parent.node_id),
+            None => Vec::new(),
+        };
+    }
+
+    // Affiche le sous-diagramme désigné par un chemin depuis la racine
+    // (s'arrête au dernier niveau existant si le chemin n'est plus valide)
+    pub fn navigate_to(&mut self, path: &[NodeId]) {
+        if path == self.current_path().as_slice() { return; }
+        self.leave_to_root();
+        for node_id in path {
+            if !self.diagram.get_node(*node_id).is_some_and(|n| n.is_decomposed()) { break; }
+            self.enter_child_diagram(*node_id);
         }
     }
+
+    // Diagnostics de toute la hiérarchie, recalculés seulement après une modification
+    // enregistrée (une modification continue est validée une fois terminée)
+    pub fn diagnostics(&mut self) -> &[Diagnostic] {
+        let revision = self.history.revision();
+        if self.diagnostics.as_ref().is_none_or(|(validated, _)| *validated != revision) {
+            self.diagnostics = Some((revision, validate_hierarchy(&self.root_diagram())));
+        }
+        self.diagnostics.as_ref().map_or(&[], |(_, diagnostics)| diagnostics)
+    }
+
+    // Navigue vers l'élément d'un diagnostic et le sélectionne sur le canvas
+    pub fn select_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.navigate_to(&diagnostic.path);
+        self.ui_state.selection = match diagnostic.element {
+            DiagnosticElement::Node(id) if self.diagram.nodes.contains_key(&id) => Selection::node(id),
+            DiagnosticElement::Arrow(id) if self.diagram.arrows.contains_key(&id) => Selection::arrow(id),
+            _ => Selection::default(),
+        };
+    }
+
+    pub fn is_at_root(&self) -> bool {
+        self.parent_levels.is_empty()
+    }
+
+    // Reconstruit le diagramme racine complet (utilisé pour sauvegarde, export et génération)
+    pub fn root_diagram(&self) -> SadtDiagram {
+        self.root_with(self.diagram.clone())
+    }
+
+    // Diagramme racine dans lequel le niveau courant est remplacé par `level`
+    fn root_with(&self, level: SadtDiagram) -> SadtDiagram {
+        let mut current = level;
+        for parent in self.parent_levels.iter().rev() {
+            let mut diagram = parent.diagram.clone();
+            if let Some(node) = diagram.get_node_mut(parent.node_id) {
+                node.child_diagram = Some(current);
+            }
+            current = diagram;
+        }
+        current
+    }
+
+    // Dictionnaire de données, porté par le diagramme racine
+    pub fn root_dictionary(&self) -> &DataDictionary {
+        let root = self.parent_levels.first().map_or(&self.diagram, |parent| &parent.diagram);
+        &root.data_dictionary
+    }
+
+    // Référence IDEF0 (A0, A12...) du diagramme affiché et de son parent
+    pub fn diagram_reference(&self) -> DiagramReference {
+        DiagramReference::along(self.parent_levels.iter().map(|level| (&level.diagram, level.node_id)), &self.diagram)
+    }
+
+    // Nœuds décomposés menant du diagramme racine au diagramme courant
+    pub fn current_path(&self) -> Vec<NodeId> {
+        self.parent_levels.iter().map(|parent| parent.node_id).collect()
+    }
+
+    // Chemin affiché dans la barre de menu: "Racine > Nom parent > ..."
+    pub fn breadcrumb(&self) -> String {
+        let mut parts = vec!["Racine".to_string()];
+        for parent in &self.parent_levels {
+            let name = parent.diagram.get_node(parent.node_id).map(|n| n.name.clone()).unwrap_or_default();
+            parts.push(name);
+        }
+        parts.join(" > ")
+    }
+}
+
+
+// --- Modifications annulables du diagramme ---
+impl AppState {
+    // Applique une modification au diagramme courant et l'enregistre dans l'historique
+    // (aucune étape n'est créée si le diagramme n'a pas changé)
+    pub fn edit<R>(&mut self, label: &str, f: impl FnOnce(&mut SadtDiagram) -> R) -> R {
+        let before = self.diagram.clone();
+        let result = f(&mut self.diagram);
+        if self.diagram != before {
+            self.record_edit(label, before);
+        }
+        result
+    }
+
+    // Modification de toute la hiérarchie (ex: dictionnaire de données), quel que soit
+    // le niveau affiché; la vue et la sélection sont conservées
+    pub fn edit_root(&mut self, label: &str, f: impl FnOnce(&mut SadtDiagram)) {
+        self.end_edit("Modification");
+        let before = self.root_diagram();
+        let mut root = before.clone();
+        f(&mut root);
+        if root == before { return; }
+        let path = self.current_path();
+        self.history.push(HistoryEntry { label: label.to_string(), root: before, path: path.clone() });
+
+        let (zoom, pan) = (self.zoom, self.pan);
+        let selection = self.ui_state.selection.clone();
+        self.diagram = root;
+        self.parent_levels.clear();
+        self.navigate_to(&path);
+        self.zoom = zoom;
+        self.pan = pan;
+        self.ui_state.selection = selection;
+        self.ui_state.selection.retain_existing(&self.diagram);
+    }
+
+    // Début d'une modification continue (drag): une seule étape sera créée à la fin
+    pub fn begin_edit(&mut self) {
+        if self.pending_edit.is_none() {
+            self.pending_edit = Some(self.diagram.clone());
+        }
+    }
+
+    pub fn end_edit(&mut self, label: &str) {
+        if let Some(before) = self.pending_edit.take() {
+            if self.diagram != before {
+                self.record_edit(label, before);
+            }
+        }
+    }
+
+    fn record_edit(&mut self, label: &str, before: SadtDiagram) {
+        let entry = HistoryEntry { label: label.to_string(), root: self.root_with(before), path: self.current_path() };
+        self.history.push(entry);
+    }
+
+    fn current_entry(&self) -> HistoryEntry {
+        HistoryEntry { label: String::new(), root: self.root_diagram(), path: self.current_path() }
+    }
+
+    pub fn undo(&mut self) {
+        self.end_edit("Modification");
+        let current = self.current_entry();
+        match self.history.undo(current) {
+            Some(entry) => { log::info!("Annulation: {}", entry.label); self.restore(entry); }
+            None => log::debug!("Rien à annuler"),
+        }
+    }
+
+    pub fn redo(&mut self) {
+        let current = self.current_entry();
+        match self.history.redo(current) {
+            Some(entry) => { log::info!("Rétablissement: {}", entry.label); self.restore(entry); }
+            None => log::debug!("Rien à rétablir"),
+        }
+    }
+
+    // Remplace tout le diagramme et redescend vers le sous-diagramme enregistré
+    fn restore(&mut self, entry: HistoryEntry) {
+        let same_level = entry.path == self.current_path();
+        let (zoom, pan) = (self.zoom, self.pan);
+        self.diagram = entry.root;
+        self.parent_levels.clear();
+        self.navigate_to(&entry.path);
+        if same_level {
+            self.zoom = zoom;
+            self.pan = pan;
+        }
+        self.ui_state.selection.clear();
+    }
 }
 
 // --- Implémentations des méthodes pour RustSadtApp ---
@@ -83,7 +369,7 @@ impl RustSadtApp {
                      log::info!("CodeGenerator initialisé.");
                  }
                  Err(e) => {
-                     self.show_error_popup(ctx, &e.into()); // Assurer conversion en RustSadtError si nécessaire
+                     self.show_error_popup(ctx, &e);
                      return None;
                  }
              }
@@ -94,6 +380,8 @@ impl RustSadtApp {
     // --- Actions du menu ---
     fn file_new(&mut self) {
         self.state.diagram = SadtDiagram::new();
+        self.state.parent_levels.clear();
+        self.state.history.clear();
         self.state.ui_state = UiState::default();
         self.state.current_file_path = None;
         self.state.generated_code = None;
@@ -107,6 +395,8 @@ impl RustSadtApp {
         match load_diagram_dialog() {
             Ok(Some((diagram, path))) => {
                 self.state.diagram = diagram;
+                self.state.parent_levels.clear();
+                self.state.history.clear();
                 self.state.current_file_path = Some(path.clone()); // Cloner car path est utilisé dans le log
                 self.state.ui_state = UiState::default();
                 self.state.generated_code = None;
@@ -123,10 +413,33 @@ impl RustSadtApp {
         }
     }
 
+    // Diagramme construit à partir d'un module Rust: il reste à l'enregistrer
+    fn file_import_rust(&mut self, ctx: &egui::Context) {
+        match crate::dialogs::import_rust_dialog() {
+            Ok(Some((diagram, path))) => {
+                self.state.diagram = diagram;
+                self.state.parent_levels.clear();
+                self.state.history.clear();
+                self.state.current_file_path = None;
+                self.state.ui_state = UiState::default();
+                self.state.generated_code = None;
+                self.state.generated_doc = None;
+                self.state.zoom = 1.0;
+                self.state.pan = Vec2::ZERO;
+                log::info!("Diagramme construit à partir du module Rust: {}", path.display());
+            }
+            Ok(None) => { log::info!("Import annulé par l'utilisateur."); }
+            Err(e) => {
+                log::error!("Erreur lors de l'import du module Rust: {}", e);
+                self.show_error_popup(ctx, &e);
+            }
+        }
+    }
+
     fn file_save(&mut self, ctx: &egui::Context) {
         if let Some(path) = &self.state.current_file_path.clone() {
             log::info!("Tentative de sauvegarde vers: {}", path.display());
-            match save_diagram(&self.state.diagram, path) {
+            match save_diagram(&self.state.root_diagram(), path) {
                 Ok(()) => { log::info!("Diagramme sauvegardé avec succès."); }
                 Err(e) => {
                     log::error!("Erreur lors de la sauvegarde: {}", e);
@@ -140,7 +453,7 @@ impl RustSadtApp {
     }
 
      fn file_save_as(&mut self, ctx: &egui::Context) {
-        match save_diagram_dialog(&self.state.diagram) {
+        match save_diagram_dialog(&self.state.root_diagram()) {
             Ok(Some(path)) => {
                 self.state.current_file_path = Some(path.clone());
                 log::info!("Diagramme sauvegardé (sous...) dans: {}", path.display());
@@ -156,7 +469,7 @@ impl RustSadtApp {
     // Action pour exporter en SVG
     fn file_export_svg(&mut self, ctx: &egui::Context) {
         log::info!("Début export SVG...");
-        match crate::persistence::export_svg_dialog(&self.state.diagram) {
+        match crate::dialogs::export_svg_dialog(&self.state.root_diagram(), self.state.export_theme) {
             Ok(Some(path)) => {
                 log::info!("Export SVG réussi vers: {}", path.display());
             }
@@ -170,12 +483,29 @@ impl RustSadtApp {
         }
     }
 
+    // Action pour exporter en PNG (options choisies dans la fenêtre d'export)
+    fn file_export_png(&mut self, ctx: &egui::Context) {
+        log::info!("Début export PNG...");
+        let options = PngExportOptions { theme: self.state.export_theme, ..self.state.png_export_options };
+        match crate::dialogs::export_png_dialog(&self.state.root_diagram(), &options) {
+            Ok(Some(path)) => {
+                log::info!("Export PNG réussi vers: {}", path.display());
+            }
+            Ok(None) => {
+                 log::info!("Export PNG annulé.");
+            }
+            Err(e) => {
+                 log::error!("Erreur lors de l'export PNG: {}", e);
+                self.show_error_popup(ctx, &e);
+            }
+        }
+    }
 
     fn generate_code(&mut self, ctx: &egui::Context) {
         if self.ensure_code_generator(ctx).is_some() {
             log::debug!("CodeGenerator obtenu, tentative de génération de code...");
             let generator = self.state.code_generator.as_ref().unwrap();
-            let diagram = &self.state.diagram;
+            let diagram = &self.state.root_diagram();
             let module_name = self.state.current_file_path
                 .as_ref()
                 .and_then(|p| p.file_stem())
@@ -190,7 +520,7 @@ impl RustSadtApp {
                 }
                 Err(e) => {
                     log::error!("Erreur DANS generate_rust_module: {}", e);
-                    self.show_error_popup(ctx, &e.into()); // Assurer conversion en RustSadtError
+                    self.show_error_popup(ctx, &e);
                 }
             }
         } else {
@@ -198,11 +528,39 @@ impl RustSadtApp {
         }
      }
 
+    // Régénère dans un fichier Rust existant en conservant les corps écrits à la main
+    fn regenerate_code_file(&mut self, ctx: &egui::Context) {
+        if self.ensure_code_generator(ctx).is_none() {
+            log::warn!("Impossible d'obtenir CodeGenerator pour régénérer le code.");
+            return;
+        }
+        let generator = self.state.code_generator.as_ref().unwrap();
+        let module_name = self.state.current_file_path
+            .as_ref()
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("generated_module");
+        match crate::dialogs::regenerate_code_dialog(generator, &self.state.root_diagram(), module_name) {
+            Ok(Some((path, report))) => {
+                log::info!("Code régénéré dans {} ({} corps conservé(s), {} conflit(s))",
+                    path.display(), report.preserved.len(), report.conflicts.len());
+                self.state.regeneration_report = Some((path, report));
+            }
+            Ok(None) => {
+                log::info!("Régénération annulée.");
+            }
+            Err(e) => {
+                log::error!("Erreur lors de la régénération: {}", e);
+                self.show_error_popup(ctx, &e);
+            }
+        }
+    }
+
     fn generate_docs(&mut self, ctx: &egui::Context) {
         if self.ensure_code_generator(ctx).is_some() {
             log::debug!("CodeGenerator obtenu, tentative de génération de doc...");
              let generator = self.state.code_generator.as_ref().unwrap();
-             let diagram = &self.state.diagram;
+             let diagram = &self.state.root_diagram();
 
              log::info!("Génération de la documentation Markdown...");
              match generator.generate_markdown_doc(diagram) {
@@ -212,7 +570,7 @@ impl RustSadtApp {
                 }
                 Err(e) => {
                     log::error!("Erreur DANS generate_markdown_doc: {}", e);
-                     self.show_error_popup(ctx, &e.into()); // Assurer conversion en RustSadtError
+                     self.show_error_popup(ctx, &e);
                 }
             }
         } else {
@@ -225,6 +583,15 @@ impl RustSadtApp {
 impl eframe::App for RustSadtApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
 
+        // --- Raccourcis Undo/Redo (laissés aux champs texte quand ils ont le focus) ---
+        if !ctx.wants_keyboard_input() {
+            let redo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND | egui::Modifiers::SHIFT, egui::Key::Z);
+            let undo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+            // Tester Ctrl+Shift+Z avant Ctrl+Z, sinon ce dernier le consommerait
+            if ctx.input_mut(|i| i.consume_shortcut(&redo_shortcut)) { self.state.redo(); }
+            else if ctx.input_mut(|i| i.consume_shortcut(&undo_shortcut)) { self.state.undo(); }
+        }
+
         // --- Menu Bar ---
         TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
@@ -234,42 +601,160 @@ impl eframe::App for RustSadtApp {
                     if ui.button("Sauvegarder").clicked() { self.file_save(ctx); ui.close_menu(); }
                     if ui.button("Sauvegarder Sous...").clicked() { self.file_save_as(ctx); ui.close_menu(); }
                     ui.separator();
+                    if ui.button("Importer un module Rust...").clicked() { self.file_import_rust(ctx); ui.close_menu(); }
                     if ui.button("Exporter SVG...").clicked() { self.file_export_svg(ctx); ui.close_menu(); }
+                    if ui.button("Exporter PNG...").clicked() { self.state.show_png_export = true; ui.close_menu(); }
+                    ui.menu_button("Thème d'export", |ui| {
+                        for theme in ExportTheme::ALL {
+                            ui.radio_value(&mut self.state.export_theme, theme, theme.label());
+                        }
+                    });
                     ui.separator();
                     if ui.button("Quitter").clicked() { ctx.send_viewport_cmd(ViewportCommand::Close); }
                 });
                  ui.menu_button("Edition", |ui| {
-                    if ui.button("Annuler (Undo)").clicked() { log::warn!("Undo non implémenté"); ui.close_menu();}
-                    if ui.button("Rétablir (Redo)").clicked() { log::warn!("Redo non implémenté"); ui.close_menu();}
+                    let undo_text = match self.state.history.undo_label() {
+                        Some(label) => format!("Annuler (Undo): {}", label),
+                        None => "Annuler (Undo)".to_string(),
+                    };
+                    let redo_text = match self.state.history.redo_label() {
+                        Some(label) => format!("Rétablir (Redo): {}", label),
+                        None => "Rétablir (Redo)".to_string(),
+                    };
+                    let can_undo = self.state.history.undo_label().is_some();
+                    let can_redo = self.state.history.redo_label().is_some();
+                    if ui.add_enabled(can_undo, egui::Button::new(undo_text).shortcut_text("Ctrl+Z")).clicked() { self.state.undo(); ui.close_menu(); }
+                    if ui.add_enabled(can_redo, egui::Button::new(redo_text).shortcut_text("Ctrl+Shift+Z")).clicked() { self.state.redo(); ui.close_menu(); }
                      ui.separator();
+                     clipboard_menu(ctx, ui, &mut self.state);
+                     ui.separator();
+                     if ui.button("Dictionnaire de données...").clicked() {
+                         self.state.dictionary_editor = Some(DictionaryEditor::new(self.state.root_dictionary()));
+                         ui.close_menu();
+                     }
+                     ui.menu_button("Aligner la sélection", |ui| alignment_menu(ui, &mut self.state));
+                     if ui.button("Disposition automatique").clicked() {
+                         self.state.edit("Disposition automatique", auto_layout);
+                         ui.close_menu();
+                     }
+                     if ui.button("Renuméroter les activités").on_hover_text("Numéros 1, 2, 3... dans l'ordre actuel, fixés").clicked() {
+                         self.state.edit("Renuméroter", renumber);
+                         ui.close_menu();
+                     }
+                     if ui.button("Renuméroter par dominance").on_hover_text("Numéros 1, 2, 3... dans l'ordre de dominance (escalier), fixés").clicked() {
+                         self.state.edit("Renuméroter par dominance", renumber_by_dominance);
+                         ui.close_menu();
+                     }
                      if ui.button("Ajouter Nœud").clicked() {
                           // Position ajout via menu: pour l'instant fixe dans le monde visible initial
                           // Idéalement, utiliser le centre de la vue actuelle transformé en monde
-                          let pos_monde_vec = self.state.pan + egui::vec2(200.0, 150.0) / self.state.zoom; // Approximation Vec2
-                         let pos_monde = Pos2::new(pos_monde_vec.x, pos_monde_vec.y); // <<< Conversion Vec2 -> Pos2
+                          let mut pos_monde = Point::ZERO + self.state.pan + Vec2::new(200.0, 150.0) / self.state.zoom; // Approximation
+                          if self.state.grid.snap { pos_monde = self.state.grid.snap_point(pos_monde); }
                          let node_name = format!("Activité {}", self.state.diagram.nodes.len() + 1);
-                         let new_node_id = self.state.diagram.add_node(node_name, pos_monde);
-                         if let Some(node) = self.state.diagram.get_node_mut(new_node_id) {
-                            node.algorithm = "add".to_string();
-                         }
+                         self.state.edit("Ajouter nœud", |diagram| diagram.add_node(node_name, pos_monde));
                          log::info!("Nœud ajouté via menu.");
                          ui.close_menu();
                      }
+                });
+                 ui.menu_button("Navigation", |ui| {
+                    let selected = self.state.ui_state.selection.single_node()
+                        .filter(|node_id| self.state.diagram.get_node(*node_id).is_some_and(|n| n.is_decomposed()));
+                    if ui.add_enabled(selected.is_some(), egui::Button::new("Ouvrir le sous-diagramme")).clicked() {
+                        if let Some(node_id) = selected { self.state.enter_child_diagram(node_id); }
+                        ui.close_menu();
+                    }
+                    if ui.add_enabled(!self.state.is_at_root(), egui::Button::new("Remonter au parent")).clicked() {
+                        self.state.leave_child_diagram(); ui.close_menu();
+                    }
+                    if ui.add_enabled(!self.state.is_at_root(), egui::Button::new("Revenir à la racine")).clicked() {
+                        self.state.leave_to_root(); ui.close_menu();
+                    }
+
+                });
+                 ui.menu_button("Affichage", |ui| {
+                    let grid = &mut self.state.grid;
+                    ui.checkbox(&mut grid.visible, "Grille");
+                    ui.checkbox(&mut grid.snap, "Magnétisme de la grille");
+                    ui.checkbox(&mut grid.guides, "Guides d'alignement");
+                    ui.checkbox(&mut self.state.show_form, "Cartouche IDEF0");
+                    ui.horizontal(|ui| {
+                        ui.label("Pas de la grille:");
+                        ui.add(egui::DragValue::new(&mut grid.spacing).clamp_range(5.0..=200.0).speed(1.0));
+                    });
+                    ui.label("Maintenir Alt pour déplacer ou redimensionner sans magnétisme");
+                    ui.separator();
+                    let properties = &mut self.state.properties;
+                    ui.checkbox(&mut properties.visible, "Panneau des propriétés");
+                    ui.horizontal(|ui| {
+                        ui.label("Ancré à:");
+                        ui.radio_value(&mut properties.dock, PanelDock::Left, "Gauche");
+                        ui.radio_value(&mut properties.dock, PanelDock::Right, "Droite");
+                    });
+                });
+                 ui.menu_button("Vérifier", |ui| {
+                    if ui.checkbox(&mut self.state.show_diagnostics, "Panneau de diagnostics").clicked() { ui.close_menu(); }
+                });
+                 ui.menu_button("Simulation", |ui| {
+                    if ui.button("Simuler le diagramme...").clicked() {
+                        self.state.simulation.get_or_insert_with(SimulationPanel::default);
+                        ui.close_menu();
+                    }
                 });
                  ui.menu_button("Générer", |ui| {
                     if ui.button("Générer Code Rust").clicked() { self.generate_code(ctx); ui.close_menu(); }
+                    if ui.button("Régénérer dans un fichier Rust...").clicked() { self.regenerate_code_file(ctx); ui.close_menu(); }
                     if ui.button("Générer Documentation Markdown").clicked() { self.generate_docs(ctx); ui.close_menu(); }
                 });
+                ui.separator();
+                if !self.state.is_at_root() && ui.small_button("⬆").on_hover_text("Remonter au parent").clicked() {
+                    self.state.leave_child_diagram();
+                }
+                ui.label(self.state.breadcrumb());
             });
         });
 
+        // --- Panneau de diagnostics (recalculé après chaque modification tant qu'il est ouvert) ---
+        if self.state.show_diagnostics {
+            let diagnostics = self.state.diagnostics().to_vec();
+            let mut clicked = None;
+            TopBottomPanel::bottom("diagnostics_panel").resizable(true).show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Diagnostics SADT/IDEF0").strong());
+                    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+                    ui.label(format!("{} erreur(s), {} avertissement(s)", errors, diagnostics.len() - errors));
+                });
+                if diagnostics.is_empty() {
+                    ui.label("Aucun problème détecté.");
+                }
+                egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                    for diagnostic in &diagnostics {
+                        let (icon, color) = match diagnostic.severity {
+                            Severity::Error => ("✖", egui::Color32::LIGHT_RED),
+                            Severity::Warning => ("⚠", egui::Color32::YELLOW),
+                        };
+                        let text = RichText::new(format!("{} {}", icon, diagnostic.message)).color(color);
+                        if ui.selectable_label(false, text).clicked() {
+                            clicked = Some(diagnostic.clone());
+                        }
+                    }
+                });
+            });
+            if let Some(diagnostic) = clicked {
+                self.state.select_diagnostic(&diagnostic);
+            }
+        }
+
+        // --- Propriétés de la sélection (avant le canvas, qui occupe l'espace restant) ---
+        show_properties_panel(ctx, &mut self.state);
+        // Simulation avant le canvas pour que celui-ci affiche les valeurs à jour
+        show_simulation_window(ctx, &mut self.state);
+
         // --- Main Canvas ---
         CentralPanel::default()
             .frame(Frame::dark_canvas(&ctx.style()))
             .show(ctx, |ui| {
                 // Calculer transformation
                 let canvas_rect = ui.clip_rect(); // Utiliser clip_rect pour la zone visible
-                let screen_center = canvas_rect.center();
                 let transform = crate::ui::transform::Transform::new(
                     self.state.pan,
                     self.state.zoom,
@@ -278,11 +763,16 @@ impl eframe::App for RustSadtApp {
                 let painter = ui.painter_at(canvas_rect);
 
                 // Dessiner
+                let form = self.state.show_form.then(|| {
+                    Idef0Frame::new(self.state.diagram.frame(), &self.state.diagram.metadata, &self.state.diagram_reference())
+                });
                 crate::ui::drawing::draw_diagram(
                     &self.state.diagram,
                     &transform,
                     &painter,
-                    &self.state.ui_state
+                    &self.state.ui_state,
+                    &self.state.grid,
+                    form.as_ref(),
                 );
 
                 // Allouer réponse pour interactions
@@ -296,7 +786,6 @@ impl eframe::App for RustSadtApp {
                     let new_zoom = (old_zoom * zoom_delta_factor).clamp(0.05, 20.0); // Augmenter la limite min pour éviter zoom trop petit
 
                     // Recalculer transform avec l'ANCIEN zoom pour trouver le point monde sous le curseur
-                    let screen_center = response.rect.center(); // Utiliser le rect de la réponse (canvas)
                     let old_transform = crate::ui::transform::Transform::new(self.state.pan, old_zoom);
 
                     if let Some(hover_pos_screen) = response.hover_pos() {
@@ -306,7 +795,7 @@ impl eframe::App for RustSadtApp {
                         // Formule: new_pan = pivot_world.to_vec2() - screen_delta / new_zoom
                         // où screen_delta est la position du curseur relative au coin haut-gauche écran (0,0)
                         // car screen_to_world simplifié utilise pan comme coord monde sous (0,0) écran.
-                        let new_pan_vec = pivot_world.to_vec2() - hover_pos_screen.to_vec2() / new_zoom;
+                        let new_pan_vec = pivot_world.to_vec2() - Vec2::new(hover_pos_screen.x, hover_pos_screen.y) / new_zoom;
 
                         // Mettre à jour l'état SEULEMENT APRÈS les calculs
                         self.state.zoom = new_zoom;
@@ -366,6 +855,23 @@ impl eframe::App for RustSadtApp {
                  });
              if !is_open { self.state.generated_code = None; }
         }
+        if let Some((path, report)) = &self.state.regeneration_report {
+             let mut is_open = true;
+             egui::Window::new("Régénération du code")
+                 .open(&mut is_open)
+                 .show(ctx, |ui| {
+                     ui.label(format!("Fichier: {}", path.display()));
+                     if report.preserved.is_empty() {
+                         ui.label("Aucun corps de fonction modifié à la main.");
+                     } else {
+                         ui.label(format!("Corps conservés: {}", report.preserved.join(", ")));
+                     }
+                     for conflict in &report.conflicts {
+                         ui.label(RichText::new(format!("⚠ {}", conflict)).color(Color32::YELLOW));
+                     }
+                 });
+             if !is_open { self.state.regeneration_report = None; }
+        }
         if let Some(doc) = &self.state.generated_doc {
              let mut is_open = true;
              egui::Window::new("Documentation Markdown Générée")
@@ -380,6 +886,45 @@ impl eframe::App for RustSadtApp {
              if !is_open { self.state.generated_doc = None; }
          }
 
+        show_dictionary_window(ctx, &mut self.state);
+
+        if self.state.show_png_export {
+            let mut is_open = true;
+            let mut export_clicked = false;
+            egui::Window::new("Exporter PNG")
+                .open(&mut is_open)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let options = &mut self.state.png_export_options;
+                    let mut dpi = options.dpi();
+                    ui.horizontal(|ui| {
+                        ui.label("Résolution (DPI):");
+                        if ui.add(egui::DragValue::new(&mut dpi).clamp_range(24.0..=1200.0).speed(1.0)).changed() {
+                            *options = PngExportOptions::from_dpi(dpi, options.transparent, options.theme);
+                        }
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Facteur d'échelle:");
+                        ui.add(egui::DragValue::new(&mut options.scale).clamp_range(0.25..=12.5).speed(0.05));
+                    });
+                    ui.checkbox(&mut options.transparent, "Fond transparent");
+                    ui.horizontal(|ui| {
+                        ui.label("Thème:");
+                        for theme in ExportTheme::ALL {
+                            ui.radio_value(&mut self.state.export_theme, theme, theme.label());
+                        }
+                    });
+                    if ui.button("Exporter...").clicked() { export_clicked = true; }
+                });
+            if export_clicked {
+                self.state.show_png_export = false;
+                self.file_export_png(ctx);
+            } else if !is_open {
+                self.state.show_png_export = false;
+            }
+        }
+
         ctx.request_repaint(); // Important pour que le pan/zoom soit fluide
     }
 }
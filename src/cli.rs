@@ -0,0 +1,281 @@
+// src/cli.rs
+// Mode ligne de commande: exécute une action sans ouvrir l'interface graphique
+// (utile en intégration continue pour vérifier et publier les diagrammes).
+use rustsadt_core::error::{Result, RustSadtError};
+use rustsadt_core::generator::CodeGenerator;
+use rustsadt_core::layout::auto_layout_hierarchy;
+use rustsadt_core::numbering::renumber_hierarchy;
+use rustsadt_core::persistence::{export_png_hierarchy, export_svg_hierarchy, load_diagram, save_diagram, PngExportOptions};
+use rustsadt_core::reverse::diagram_from_rust_file;
+use rustsadt_core::style::ExportTheme;
+use rustsadt_core::validation::{diagnostic_location, validate_hierarchy, Severity};
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "\
+Usage: rustsadt [commande]
+Sans commande, lance l'interface graphique.
+
+Commandes:
+  validate <diagramme.ron> [--strict]
+      Vérifie les règles SADT/IDEF0 (--strict: les avertissements font aussi échouer)
+  export-svg <diagramme.ron> <sortie.svg> [--theme dark|light]
+  export-png <diagramme.ron> <sortie.png> [--dpi N | --scale F] [--transparent] [--theme dark|light]
+      Exporte le diagramme et ses sous-diagrammes (un fichier par niveau)
+  generate-code <diagramme.ron> [-o <sortie.rs> [--update]] [--module <nom>] [--templates <dossier>]
+  generate-docs <diagramme.ron> [-o <sortie.md>] [--templates <dossier>]
+      Génère le squelette Rust ou la documentation Markdown (sortie standard par défaut).
+      Les templates Tera sont lus dans ./templates sauf si --templates est donné.
+      --update régénère dans le fichier existant en conservant les corps de fonction
+      modifiés à la main; les conflits sont signalés sur la sortie d'erreur.
+  import-rust <module.rs> <sortie.ron>
+      Construit un diagramme à partir des fonctions publiques d'un module Rust
+  layout <diagramme.ron> <sortie.ron>
+      Dispose automatiquement les activités en escalier, sous-diagrammes compris
+  renumber <diagramme.ron> <sortie.ron> [--auto]
+      Fixe les numéros de boîte 1, 2, 3... dans l'ordre actuel, sous-diagrammes compris
+      (--auto: dans l'ordre de dominance, numéros choisis à la main abandonnés)
+  convert <diagramme.ron> <sortie.ron>
+      Réécrit le diagramme au format RON courant
+  help
+      Affiche cette aide
+
+Code de sortie: 0 en cas de succès, 1 en cas d'erreur, 2 si les arguments sont invalides.";
+
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+// Retourne le code de sortie si des arguments sont donnés, None pour lancer
+// l'interface graphique (aucun argument). Une commande inconnue est une erreur
+// d'usage: une faute de frappe ne doit pas ouvrir de fenêtre en intégration continue.
+pub fn run_from_args(args: &[String]) -> Option<i32> {
+    let command = args.get(1)?;
+    let options = &args[2..];
+    let result = match command.as_str() {
+        "validate" => validate_command(options),
+        "export-svg" => export_svg_command(options),
+        "export-png" => export_png_command(options),
+        "generate-code" => generate_code_command(options),
+        "generate-docs" => generate_docs_command(options),
+        "import-rust" => import_rust_command(options),
+        "layout" => layout_command(options),
+        "renumber" => renumber_command(options),
+        "convert" => convert_command(options),
+        "help" | "-h" | "--help" => { println!("{}", USAGE); return Some(0); }
+        unknown => {
+            eprintln!("Commande inconnue: {}\n\n{}", unknown, USAGE);
+            return Some(EXIT_USAGE);
+        }
+    };
+    match result {
+        Ok(()) => Some(0),
+        Err(RustSadtError::Ui(message)) => {
+            eprintln!("{}", message);
+            Some(EXIT_USAGE)
+        }
+        Err(e) => {
+            eprintln!("Erreur: {}", e);
+            Some(EXIT_FAILURE)
+        }
+    }
+}
+
+// Arguments d'une commande: positionnels d'un côté, options (--nom [valeur]) de l'autre
+struct CommandArgs<'a> {
+    positional: Vec<&'a str>,
+    flags: Vec<&'a str>,
+    values: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> CommandArgs<'a> {
+    // `valued` liste les options qui attendent une valeur; `flags` celles qui n'en ont pas
+    fn parse(args: &'a [String], flags: &[&str], valued: &[&str]) -> Result<Self> {
+        let mut parsed = CommandArgs { positional: Vec::new(), flags: Vec::new(), values: Vec::new() };
+        let mut iter = args.iter().map(String::as_str);
+        while let Some(arg) = iter.next() {
+            if valued.contains(&arg) {
+                let value = iter.next().ok_or_else(|| usage_error(&format!("{} attend une valeur", arg)))?;
+                parsed.values.push((arg, value));
+            } else if flags.contains(&arg) {
+                parsed.flags.push(arg);
+            } else if arg.starts_with('-') && arg != "-" {
+                return Err(usage_error(&format!("Option inconnue: {}", arg)));
+            } else {
+                parsed.positional.push(arg);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    fn value(&self, option: &str) -> Option<&'a str> {
+        self.values.iter().rev().find(|(name, _)| *name == option).map(|(_, value)| *value)
+    }
+
+    fn number(&self, option: &str) -> Result<Option<f32>> {
+        self.value(option)
+            .map(|v| v.parse::<f32>().ok().filter(|v| *v > 0.0).ok_or_else(|| usage_error(&format!("{} attend un nombre positif", option))))
+            .transpose()
+    }
+
+    fn theme(&self) -> Result<ExportTheme> {
+        self.value("--theme")
+            .map(|v| v.parse().map_err(|_| usage_error(&format!("Thème inconnu: {} (dark ou light)", v))))
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn positional<const N: usize>(&self) -> Result<[&'a str; N]> {
+        self.positional.as_slice().try_into().map_err(|_| usage_error("Nombre d'arguments invalide"))
+    }
+}
+
+fn usage_error(message: &str) -> RustSadtError {
+    RustSadtError::Ui(format!("{}\n\n{}", message, USAGE))
+}
+
+fn validate_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &["--strict"], &[])?;
+    let [input] = args.positional()?;
+    let diagram = load_diagram(&PathBuf::from(input))?;
+
+    let diagnostics = validate_hierarchy(&diagram);
+    for diagnostic in &diagnostics {
+        println!("[{}] {}", diagnostic_location(&diagram, &diagnostic.path), diagnostic);
+    }
+    let errors = diagnostics.iter().filter(|d| d.severity == Severity::Error).count();
+    let warnings = diagnostics.len() - errors;
+    println!("{}: {} erreur(s), {} avertissement(s)", input, errors, warnings);
+
+    let failures = if args.has("--strict") { diagnostics.len() } else { errors };
+    if failures > 0 {
+        return Err(RustSadtError::Validation(failures));
+    }
+    Ok(())
+}
+
+fn export_svg_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &[], &["--theme"])?;
+    let [input, output] = args.positional()?;
+    let diagram = load_diagram(&PathBuf::from(input))?;
+    for path in export_svg_hierarchy(&diagram, Path::new(output), args.theme()?)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn export_png_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &["--transparent"], &["--dpi", "--scale", "--theme"])?;
+    let [input, output] = args.positional()?;
+    let mut options = PngExportOptions { transparent: args.has("--transparent"), theme: args.theme()?, ..Default::default() };
+    if let Some(dpi) = args.number("--dpi")? {
+        options = PngExportOptions::from_dpi(dpi, options.transparent, options.theme);
+    }
+    if let Some(scale) = args.number("--scale")? {
+        options.scale = scale;
+    }
+
+    let diagram = load_diagram(&PathBuf::from(input))?;
+    for path in export_png_hierarchy(&diagram, Path::new(output), &options)? {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn generate_code_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &["--update"], &["-o", "--module", "--templates"])?;
+    let [input] = args.positional()?;
+    let diagram = load_diagram(&PathBuf::from(input))?;
+    // Même nom de module par défaut que dans l'interface: le nom du fichier
+    let module_name = args.value("--module")
+        .or_else(|| Path::new(input).file_stem().and_then(|s| s.to_str()))
+        .unwrap_or("generated_module");
+    let generator = code_generator(&args)?;
+    if !args.has("--update") {
+        let code = generator.generate_rust_module(&diagram, module_name)?;
+        return write_output(args.value("-o"), &code);
+    }
+
+    let output = args.value("-o").filter(|path| *path != "-").ok_or_else(|| usage_error("--update attend un fichier de sortie (-o)"))?;
+    // Un fichier absent est simplement généré
+    let existing = match std::fs::read_to_string(output) {
+        Ok(existing) => existing,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let (code, report) = generator.regenerate_rust_module(&diagram, module_name, &existing)?;
+    for function in &report.preserved {
+        eprintln!("Corps conservé: {}", function);
+    }
+    for conflict in &report.conflicts {
+        eprintln!("Conflit: {}", conflict);
+    }
+    write_output(Some(output), &code)
+}
+
+fn generate_docs_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &[], &["-o", "--templates"])?;
+    let [input] = args.positional()?;
+    let diagram = load_diagram(&PathBuf::from(input))?;
+    let doc = code_generator(&args)?.generate_markdown_doc(&diagram)?;
+    write_output(args.value("-o"), &doc)
+}
+
+fn code_generator(args: &CommandArgs) -> Result<CodeGenerator> {
+    match args.value("--templates") {
+        Some(dir) => CodeGenerator::from_dir(Path::new(dir)),
+        None => CodeGenerator::new(),
+    }
+}
+
+fn import_rust_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &[], &[])?;
+    let [input, output] = args.positional()?;
+    let diagram = diagram_from_rust_file(Path::new(input))?;
+    save_diagram(&diagram, &PathBuf::from(output))?;
+    println!("{}", output);
+    Ok(())
+}
+
+fn layout_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &[], &[])?;
+    let [input, output] = args.positional()?;
+    let mut diagram = load_diagram(&PathBuf::from(input))?;
+    auto_layout_hierarchy(&mut diagram);
+    save_diagram(&diagram, &PathBuf::from(output))?;
+    println!("{}", output);
+    Ok(())
+}
+
+fn renumber_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &["--auto"], &[])?;
+    let [input, output] = args.positional()?;
+    let mut diagram = load_diagram(&PathBuf::from(input))?;
+    renumber_hierarchy(&mut diagram, args.has("--auto"));
+    save_diagram(&diagram, &PathBuf::from(output))?;
+    println!("{}", output);
+    Ok(())
+}
+
+fn convert_command(args: &[String]) -> Result<()> {
+    let args = CommandArgs::parse(args, &[], &[])?;
+    let [input, output] = args.positional()?;
+    let diagram = load_diagram(&PathBuf::from(input))?;
+    save_diagram(&diagram, &PathBuf::from(output))?;
+    println!("{}", output);
+    Ok(())
+}
+
+// Écrit dans un fichier, ou sur la sortie standard sans -o (ou avec "-o -")
+fn write_output(path: Option<&str>, content: &str) -> Result<()> {
+    match path {
+        Some(path) if path != "-" => {
+            std::fs::write(path, content)?;
+            println!("{}", path);
+        }
+        _ => print!("{}", content),
+    }
+    Ok(())
+}
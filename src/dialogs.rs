@@ -0,0 +1,113 @@
+// src/dialogs.rs
+// Boîtes de dialogue fichiers de l'éditeur (ouverture, sauvegarde, exports)
+use rfd::FileDialog;
+use rustsadt_core::error::Result;
+use rustsadt_core::generator::CodeGenerator;
+use rustsadt_core::persistence::{
+    export_png_hierarchy, export_svg_hierarchy, load_diagram, save_diagram, PngExportOptions,
+    FILE_EXTENSION, PNG_FILE_EXTENSION, SVG_FILE_EXTENSION,
+};
+use rustsadt_core::protected::MergeReport;
+use rustsadt_core::reverse::diagram_from_rust_file;
+use rustsadt_core::sadt_model::SadtDiagram;
+use rustsadt_core::style::ExportTheme;
+use std::path::PathBuf;
+
+// Ouvre une boîte de dialogue pour choisir où sauvegarder
+pub fn save_diagram_dialog(diagram: &SadtDiagram) -> Result<Option<PathBuf>> {
+    let path = FileDialog::new()
+        .add_filter("SADT Diagram", &[FILE_EXTENSION])
+        .set_file_name("diagram.ron")
+        .save_file();
+
+    match path {
+        Some(p) => {
+            save_diagram(diagram, &p)?;
+            Ok(Some(p))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+    }
+}
+
+// Ouvre une boîte de dialogue pour choisir quel fichier charger
+pub fn load_diagram_dialog() -> Result<Option<(SadtDiagram, PathBuf)>> {
+     let path = FileDialog::new()
+        .add_filter("SADT Diagram", &[FILE_EXTENSION])
+        .pick_file();
+
+     match path {
+        Some(p) => {
+            let diagram = load_diagram(&p)?;
+            Ok(Some((diagram, p)))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+     }
+}
+
+// Ouvre une boîte de dialogue pour choisir le module Rust à rétro-concevoir en diagramme
+pub fn import_rust_dialog() -> Result<Option<(SadtDiagram, PathBuf)>> {
+    let path = FileDialog::new()
+        .add_filter("Rust", &["rs"])
+        .pick_file();
+
+    match path {
+        Some(p) => {
+            let diagram = diagram_from_rust_file(&p)?;
+            Ok(Some((diagram, p)))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+    }
+}
+
+// Ouvre une boîte de dialogue pour choisir où exporter en SVG
+pub fn export_svg_dialog(diagram: &SadtDiagram, theme: ExportTheme) -> Result<Option<PathBuf>> {
+    let path = FileDialog::new()
+        .add_filter("Scalable Vector Graphics", &[SVG_FILE_EXTENSION])
+        .set_file_name("diagram.svg")
+        .save_file();
+
+    match path {
+        Some(p) => {
+            let written = export_svg_hierarchy(diagram, &p, theme)?;
+            log::info!("{} fichier(s) SVG exporté(s)", written.len());
+            Ok(Some(p))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+    }
+}
+
+// Ouvre une boîte de dialogue pour choisir où exporter en PNG
+pub fn export_png_dialog(diagram: &SadtDiagram, options: &PngExportOptions) -> Result<Option<PathBuf>> {
+    let path = FileDialog::new()
+        .add_filter("Portable Network Graphics", &[PNG_FILE_EXTENSION])
+        .set_file_name("diagram.png")
+        .save_file();
+
+    match path {
+        Some(p) => {
+            let written = export_png_hierarchy(diagram, &p, options)?;
+            log::info!("{} fichier(s) PNG exporté(s)", written.len());
+            Ok(Some(p))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+    }
+}
+
+// Ouvre une boîte de dialogue pour choisir le fichier Rust à régénérer: les corps de
+// fonction modifiés à la main sont conservés (un fichier absent est simplement créé)
+pub fn regenerate_code_dialog(generator: &CodeGenerator, diagram: &SadtDiagram, module_name: &str) -> Result<Option<(PathBuf, MergeReport)>> {
+    let path = FileDialog::new()
+        .add_filter("Rust", &["rs"])
+        .set_file_name(format!("{}.rs", module_name))
+        .save_file();
+
+    match path {
+        Some(p) => {
+            let existing = if p.exists() { std::fs::read_to_string(&p)? } else { String::new() };
+            let (code, report) = generator.regenerate_rust_module(diagram, module_name, &existing)?;
+            std::fs::write(&p, code)?;
+            Ok(Some((p, report)))
+        }
+        None => Ok(None), // L'utilisateur a annulé
+    }
+}
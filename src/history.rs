@@ -0,0 +1,67 @@
+// src/history.rs
+// Historique d'annulation/rétablissement par instantanés.
+// Chaque étape conserve le diagramme racine complet tel qu'il était avant
+// la modification, ainsi que le chemin du sous-diagramme alors affiché.
+use rustsadt_core::sadt_elements::NodeId;
+use rustsadt_core::sadt_model::SadtDiagram;
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub label: String,       // Description de la modification ("Déplacer nœud", ...)
+    pub root: SadtDiagram,   // Diagramme racine à restaurer
+    pub path: Vec<NodeId>,   // Nœuds décomposés menant au diagramme affiché
+}
+
+#[derive(Debug, Default)]
+pub struct History {
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+    revision: u64, // Change à chaque étape, annulation ou rétablissement (résultats en cache)
+}
+
+impl History {
+    // Enregistre une nouvelle étape; toute branche de rétablissement est perdue
+    pub fn push(&mut self, entry: HistoryEntry) {
+        log::debug!("Historique: nouvelle étape \"{}\"", entry.label);
+        self.undo_stack.push(entry);
+        self.redo_stack.clear();
+        self.revision += 1;
+    }
+
+    // Retourne l'état à restaurer; `current` devient l'étape à rétablir
+    pub fn undo(&mut self, mut current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.undo_stack.pop()?;
+        current.label = entry.label.clone();
+        self.redo_stack.push(current);
+        self.revision += 1;
+        Some(entry)
+    }
+
+    // Retourne l'état à rétablir; `current` redevient annulable
+    pub fn redo(&mut self, mut current: HistoryEntry) -> Option<HistoryEntry> {
+        let entry = self.redo_stack.pop()?;
+        current.label = entry.label.clone();
+        self.undo_stack.push(current);
+        self.revision += 1;
+        Some(entry)
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo_stack.last().map(|e| e.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo_stack.last().map(|e| e.label.as_str())
+    }
+
+    // Un nouveau diagramme remplace l'ancien: c'est aussi une nouvelle révision
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.revision += 1;
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
@@ -2,16 +2,21 @@
 use eframe::egui;
 
 // Déclaration des modules pour qu'ils soient accessibles depuis la racine du crate
+// (le modèle, la validation, la persistance et la génération sont dans `rustsadt-core`)
 mod app;
-mod error;
-mod generator;
-mod persistence;
-mod sadt_elements;
-mod sadt_model;
+mod cli;
+mod dialogs;
+mod history;
 // Déclaration du module ui et de ses sous-modules
 mod ui {
+    pub mod clipboard;
+    pub mod dictionary;
     pub mod drawing;
     pub mod interaction;
+    pub mod properties;
+    pub mod selection;
+    pub mod simulation;
+    pub mod snapping;
     pub mod transform;
     // Le fichier `mod.rs` est souvent implicite, pas besoin de 'mod_impl'
 }
@@ -20,6 +25,12 @@ fn main() -> Result<(), eframe::Error> {
     // Setup logging
     env_logger::init(); // Ou un autre logger comme tracing
 
+    // Mode ligne de commande (aucune fenêtre n'est ouverte)
+    let args: Vec<String> = std::env::args().collect();
+    if let Some(exit_code) = cli::run_from_args(&args) {
+        std::process::exit(exit_code);
+    }
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1024.0, 768.0])
@@ -0,0 +1,72 @@
+// src/ui/clipboard.rs
+// Copier, couper, coller et dupliquer la sélection. Le fragment copié (texte RON) est
+// gardé dans l'application et placé dans le presse-papiers du système: Ctrl+V colle
+// aussi un fragment copié depuis une autre fenêtre de RustSADT.
+use crate::app::AppState;
+use crate::ui::interaction::remove_selection;
+use crate::ui::selection::Selection;
+use egui::Context;
+use rustsadt_core::clipboard::DiagramFragment;
+use rustsadt_core::geometry::{vec2, Vec2};
+
+const PASTE_OFFSET: Vec2 = vec2(30.0, 30.0); // Décalage monde de chaque collage successif
+
+// Texte copié et nombre de collages déjà faits (pour décaler chaque copie)
+#[derive(Debug, Default, Clone)]
+pub struct ClipboardState {
+    pub text: Option<String>,
+    pub paste_count: u32,
+}
+
+// Copie les nœuds sélectionnés et les flèches qui les relient. Retourne faux si rien n'est copié.
+pub fn copy_selection(ctx: &Context, app_state: &mut AppState) -> bool {
+    let fragment = DiagramFragment::copy(&app_state.diagram, &app_state.ui_state.selection.nodes);
+    if fragment.is_empty() { return false; }
+    match fragment.to_text() {
+        Ok(text) => {
+            ctx.output_mut(|output| output.copied_text = text.clone());
+            app_state.clipboard = ClipboardState { text: Some(text), paste_count: 0 };
+            log::info!("{} nœud(s) et {} flèche(s) copiés", fragment.nodes.len(), fragment.arrows.len());
+            true
+        }
+        Err(e) => { log::error!("Copie impossible: {}", e); false }
+    }
+}
+
+pub fn cut_selection(ctx: &Context, app_state: &mut AppState) {
+    if copy_selection(ctx, app_state) {
+        remove_selection(app_state, "Couper");
+    }
+}
+
+// Colle le fragment copié dans le diagramme affiché
+pub fn paste(app_state: &mut AppState) {
+    if let Some(text) = app_state.clipboard.text.clone() {
+        paste_text(app_state, &text);
+    }
+}
+
+// Colle un texte du presse-papiers s'il contient un fragment de diagramme
+pub fn paste_text(app_state: &mut AppState, text: &str) {
+    let fragment = match DiagramFragment::from_text(text) {
+        Ok(fragment) if !fragment.is_empty() => fragment,
+        Ok(_) => return,
+        Err(e) => { log::debug!("Presse-papiers ignoré (pas un fragment de diagramme): {}", e); return; }
+    };
+    // Un fragment venu d'ailleurs remplace le fragment copié; collé plusieurs fois, il se décale à chaque fois
+    if app_state.clipboard.text.as_deref() != Some(text) {
+        app_state.clipboard = ClipboardState { text: Some(text.to_string()), paste_count: 0 };
+    }
+    app_state.clipboard.paste_count += 1;
+    let offset = PASTE_OFFSET * app_state.clipboard.paste_count as f32;
+    let (nodes, arrows) = app_state.edit("Coller", |diagram| fragment.paste_into(diagram, offset));
+    app_state.ui_state.selection = Selection { nodes, arrows };
+}
+
+// Copie-colle la sélection sur place (décalée), sans toucher au presse-papiers
+pub fn duplicate_selection(app_state: &mut AppState) {
+    let fragment = DiagramFragment::copy(&app_state.diagram, &app_state.ui_state.selection.nodes);
+    if fragment.is_empty() { return; }
+    let (nodes, arrows) = app_state.edit("Dupliquer", |diagram| fragment.paste_into(diagram, PASTE_OFFSET));
+    app_state.ui_state.selection = Selection { nodes, arrows };
+}
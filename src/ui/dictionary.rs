@@ -0,0 +1,163 @@
+// src/ui/dictionary.rs
+// Fenêtre d'édition du dictionnaire de données. Les modifications portent sur une
+// copie de travail, appliquée en une seule étape d'annulation à la validation.
+use crate::app::AppState;
+use egui::{Color32, Context, RichText, Ui};
+use rustsadt_core::data_dictionary::{DataDictionary, DataField, DataType, DataTypeKind};
+
+pub struct DictionaryEditor {
+    entries: Vec<DraftType>,
+}
+
+struct DraftType {
+    original_name: Option<String>, // Nom à l'ouverture (None pour un type ajouté)
+    data_type: DataType,
+}
+
+impl DictionaryEditor {
+    pub fn new(dictionary: &DataDictionary) -> Self {
+        let entries = dictionary.types.iter()
+            .map(|data_type| DraftType { original_name: Some(data_type.name.clone()), data_type: data_type.clone() })
+            .collect();
+        DictionaryEditor { entries }
+    }
+
+    fn dictionary(&self) -> DataDictionary {
+        DataDictionary { types: self.entries.iter().map(|entry| entry.data_type.clone()).collect() }
+    }
+
+    // Renommages (ancien, nouveau) et suppressions (ancien, None) à reporter sur les flèches
+    fn retypings(&self, dictionary: &DataDictionary) -> Vec<(String, Option<String>)> {
+        let mut retypings = Vec::new();
+        for original in dictionary.types.iter().map(|t| &t.name) {
+            match self.entries.iter().find(|entry| entry.original_name.as_ref() == Some(original)) {
+                Some(entry) if entry.data_type.name != *original => retypings.push((original.clone(), Some(entry.data_type.name.clone()))),
+                Some(_) => {}
+                None => retypings.push((original.clone(), None)),
+            }
+        }
+        retypings
+    }
+}
+
+// Affiche la fenêtre si elle est ouverte
+pub fn show_dictionary_window(ctx: &Context, app_state: &mut AppState) {
+    let Some(mut editor) = app_state.dictionary_editor.take() else { return; };
+    let mut is_open = true;
+    let mut apply = false;
+    let mut cancel = false;
+    egui::Window::new("Dictionnaire de données")
+        .open(&mut is_open)
+        .default_width(420.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                let mut removed = None;
+                for (index, entry) in editor.entries.iter_mut().enumerate() {
+                    let header = format!("{} ({})", entry.data_type.name, entry.data_type.kind.label());
+                    egui::CollapsingHeader::new(header).id_source(index).show(ui, |ui| {
+                        if data_type_editor(ui, index, &mut entry.data_type) {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    editor.entries.remove(index);
+                }
+                if editor.entries.is_empty() {
+                    ui.label("Aucun type défini.");
+                }
+            });
+            if ui.button("➕ Nouveau type").clicked() {
+                let name = editor.dictionary().unused_name();
+                editor.entries.push(DraftType { original_name: None, data_type: DataType { name, description: String::new(), kind: DataTypeKind::Struct(Vec::new()) } });
+            }
+
+            ui.separator();
+            let problems = editor.dictionary().problems();
+            for problem in &problems {
+                ui.label(RichText::new(format!("✖ {}", problem)).color(Color32::LIGHT_RED));
+            }
+            ui.horizontal(|ui| {
+                if ui.add_enabled(problems.is_empty(), egui::Button::new("Valider")).clicked() { apply = true; }
+                if ui.button("Annuler").clicked() { cancel = true; }
+            });
+        });
+
+    if apply {
+        let dictionary = editor.dictionary();
+        let retypings = editor.retypings(app_state.root_dictionary());
+        app_state.edit_root("Modifier le dictionnaire de données", |root| {
+            for (old, new) in &retypings {
+                root.retype_arrows(old, new.as_deref());
+            }
+            root.data_dictionary = dictionary;
+        });
+    }
+    if !(apply || cancel || !is_open) {
+        app_state.dictionary_editor = Some(editor);
+    }
+}
+
+// Éditeur d'un type; retourne vrai si l'utilisateur demande sa suppression
+fn data_type_editor(ui: &mut Ui, index: usize, data_type: &mut DataType) -> bool {
+    ui.horizontal(|ui| {
+        ui.label("Nom:");
+        ui.text_edit_singleline(&mut data_type.name);
+    });
+    ui.horizontal(|ui| {
+        ui.label("Description:");
+        ui.text_edit_singleline(&mut data_type.description);
+    });
+
+    // Changer de nature repart d'une définition vide
+    let kinds = [DataTypeKind::Struct(Vec::new()), DataTypeKind::Enum(Vec::new()), DataTypeKind::Alias(String::new())];
+    egui::ComboBox::from_id_source(("data_type_kind", index))
+        .selected_text(data_type.kind.label())
+        .show_ui(ui, |ui| {
+            for kind in kinds {
+                let selected = std::mem::discriminant(&kind) == std::mem::discriminant(&data_type.kind);
+                if ui.selectable_label(selected, kind.label()).clicked() && !selected {
+                    data_type.kind = kind;
+                }
+            }
+        });
+
+    match &mut data_type.kind {
+        DataTypeKind::Struct(fields) => {
+            let mut removed = None;
+            for (i, field) in fields.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(&mut field.name).hint_text("champ").desired_width(120.0));
+                    ui.label(":");
+                    ui.add(egui::TextEdit::singleline(&mut field.ty).hint_text("type").desired_width(160.0));
+                    if ui.small_button("🗑").on_hover_text("Supprimer le champ").clicked() { removed = Some(i); }
+                });
+            }
+            if let Some(i) = removed { fields.remove(i); }
+            if ui.small_button("➕ Champ").clicked() {
+                fields.push(DataField { name: format!("champ{}", fields.len() + 1), ty: "String".to_string() });
+            }
+        }
+        DataTypeKind::Enum(variants) => {
+            let mut removed = None;
+            for (i, variant) in variants.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.add(egui::TextEdit::singleline(variant).hint_text("Variante").desired_width(160.0));
+                    if ui.small_button("🗑").on_hover_text("Supprimer la variante").clicked() { removed = Some(i); }
+                });
+            }
+            if let Some(i) = removed { variants.remove(i); }
+            if ui.small_button("➕ Variante").clicked() {
+                variants.push(format!("Variante{}", variants.len() + 1));
+            }
+        }
+        DataTypeKind::Alias(path) => {
+            ui.horizontal(|ui| {
+                ui.label("Type Rust:");
+                ui.add(egui::TextEdit::singleline(path).hint_text("std::path::PathBuf"));
+            });
+        }
+    }
+
+    ui.button("Supprimer le type").clicked()
+}
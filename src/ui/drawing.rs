@@ -1,42 +1,116 @@
 // src/ui/drawing.rs
-use crate::sadt_model::{Arrow, ProcessNode, SadtDiagram};
-use crate::sadt_elements::{ArrowType, NodeId, ArrowId, Side, ConnectionPoint};
+use rustsadt_core::balancing::ParentIcom;
+use rustsadt_core::frame::Idef0Frame;
+use rustsadt_core::numbering::box_numbers;
+use rustsadt_core::sadt_model::{Arrow, ProcessNode, SadtDiagram};
+use rustsadt_core::sadt_elements::{ArrowEnd, ArrowType, NodeId, ArrowId, IcomCode, Side, ConnectionPoint};
+use rustsadt_core::geometry::{Point, Rect as WorldRect, Vec2 as WorldVec2};
+use rustsadt_core::routing::{junction_point, path_midpoint};
+use rustsadt_core::style::{self, Color, ARROW_HEAD_SIZE, ARROW_STROKE_WIDTH, BOX_NUMBER_INSET, DECOMPOSED_INSET, FRAME_CAPTION_FONT_SIZE, FRAME_VALUE_FONT_SIZE, ICOM_LABEL_OFFSET, JUNCTION_RADIUS, LABEL_FONT_SIZE, LINE_HEIGHT, NODE_CORNER_RADIUS, NODE_FONT_SIZE};
 use egui::{vec2, Color32, Painter, Pos2, Rect, Stroke, Vec2, FontId, Align2};
+use crate::ui::selection::Selection;
+use crate::ui::snapping::{GridSettings, Guide};
 use crate::ui::transform::Transform; // Importer Transform
+use std::collections::HashMap;
 
-// Constantes pour le dessin
-const NODE_CORNER_RADIUS: f32 = 5.0;
-const ARROW_HEAD_SIZE: f32 = 10.0; // Taille de base de la tête de flèche (sera scalée)
+// Constantes propres à l'éditeur (tailles et couleurs communes dans `rustsadt_core::style`)
 pub const CONNECTION_POINT_RADIUS: f32 = 4.0; // Rayon monde du point de connexion
+const BOUNDARY_STUB_LENGTH: f32 = 30.0; // Longueur monde d'un port ICOM non connecté
+pub const WAYPOINT_HANDLE_SIZE: f32 = 8.0; // Côté écran de la poignée d'un point de passage
+pub const RESIZE_HANDLE_SIZE: f32 = 8.0; // Côté écran d'une poignée de redimensionnement (coins du nœud)
+const MIN_GRID_STEP: f32 = 8.0; // Écart écran minimal entre deux lignes de grille (sinon une sur cinq)
+const GRID_MINOR: Color32 = Color32::from_gray(36);
+const GRID_MAJOR: Color32 = Color32::from_gray(48);
+const GUIDE_COLOR: Color32 = Color32::from_rgb(255, 0, 200);
+
+const NODE_FILL: Color32 = color32(style::NODE_FILL);
+const NODE_STROKE: Color32 = color32(style::NODE_STROKE);
+const NODE_TEXT: Color32 = color32(style::NODE_TEXT);
+const FRAME_STROKE: Color32 = color32(style::FRAME_STROKE);
+
+const fn color32(color: Color) -> Color32 {
+    Color32::from_rgb(color.r, color.g, color.b)
+}
 
 // Structure d'état UI (pas de changements ici)
 #[derive(Debug, Default, Clone)]
 pub struct UiState {
-    pub selected_node: Option<NodeId>,
-    pub selected_arrow: Option<ArrowId>,
-    pub dragging_node: Option<NodeId>, // Peut être utile pour le curseur plus tard
-    pub arrow_creation_start: Option<ConnectionPoint>,
-    pub mouse_pos: Pos2, // Coordonnées MONDE de la souris
+    pub selection: Selection,
+    pub rubber_band: Option<Point>, // Coin MONDE de départ de la sélection rectangulaire en cours
+    pub dragging_nodes: bool, // Nœuds sélectionnés en cours de déplacement (une étape d'annulation par drag)
+    pub dragging_waypoint: Option<(ArrowId, usize)>, // Point de passage en cours de déplacement
+    pub dragging_slot: Option<(ArrowId, bool)>, // Extrémité (source si vrai) glissée le long de son côté
+    pub resizing_node: Option<(NodeId, Point)>, // Nœud redimensionné et coin opposé (fixe) à la poignée
+    pub guides: Vec<Guide>, // Guides d'alignement affichés pendant un déplacement
+    pub context_menu_pos: Point, // Position MONDE du dernier clic droit
+    pub arrow_creation_start: Option<ArrowEnd>,
+    pub mouse_pos: Point, // Coordonnées MONDE de la souris
     pub renaming_node: Option<NodeId>,
     pub renaming_arrow: Option<ArrowId>,
     pub renaming_label_text: String,
+    // Codes ICOM de la boîte parente quand on édite un sous-diagramme
+    pub boundary_ports: Vec<ParentIcom>,
+    pub simulation: Option<SimulationOverlay>, // Simulation en cours sur le diagramme affiché
 }
 
-// Fonction principale de dessin
-pub fn draw_diagram(diagram: &SadtDiagram, transform: &Transform, painter: &Painter, ui_state: &UiState) {
+// État d'une simulation affiché sur le canvas
+#[derive(Debug, Default, Clone)]
+pub struct SimulationOverlay {
+    pub values: HashMap<ArrowId, f64>, // Valeur courante de chaque flèche
+    pub active_node: Option<NodeId>,  // Dernière activité exécutée
+    pub error_nodes: Vec<NodeId>,
+}
+
+// Fonction principale de dessin (`form`: cartouche IDEF0 affiché autour du cadre)
+pub fn draw_diagram(diagram: &SadtDiagram, transform: &Transform, painter: &Painter, ui_state: &UiState, grid: &GridSettings, form: Option<&Idef0Frame>) {
+    if grid.visible {
+        draw_grid(transform, painter, grid);
+    }
+
+    if let Some(form) = form {
+        draw_form(form, transform, painter);
+    }
+    // Cadre et ports ICOM (cartouche, sous-diagramme ou flèches frontières présentes)
+    if form.is_some() || !ui_state.boundary_ports.is_empty() || diagram.boundary_arrows().next().is_some() {
+        draw_frame_and_ports(diagram, transform, painter, ui_state);
+    }
+
     // Dessiner flèches en premier (dessous)
     for arrow in diagram.arrows.values() {
         draw_arrow(arrow, diagram, transform, painter, ui_state);
     }
     // Dessiner nœuds ensuite (dessus)
+    let numbers = box_numbers(diagram);
     for node in diagram.nodes.values() {
-        draw_node(node, transform, painter, ui_state.selected_node == Some(node.id));
+        let highlight = ui_state.simulation.as_ref().and_then(|sim| {
+            if sim.error_nodes.contains(&node.id) { Some(Color32::LIGHT_RED) }
+            else if sim.active_node == Some(node.id) { Some(Color32::LIGHT_GREEN) }
+            else { None }
+        });
+        draw_node(node, numbers.get(&node.id).copied(), transform, painter, ui_state.selection.contains_node(node.id), highlight);
+    }
+
+    // Poignées de redimensionnement du nœud sélectionné seul
+    if let Some(node) = ui_state.selection.single_node().and_then(|id| diagram.get_node(id)) {
+        for corner in node_corners(node.rect) {
+            let handle = Rect::from_center_size(transform.world_to_screen(corner), Vec2::splat(RESIZE_HANDLE_SIZE));
+            painter.rect(handle, 0.0, Color32::YELLOW, Stroke::new(1.0, Color32::DARK_GRAY));
+        }
+    }
+
+    for (from, to) in &ui_state.guides {
+        painter.line_segment([transform.world_to_screen(*from), transform.world_to_screen(*to)], Stroke::new(1.0, GUIDE_COLOR));
+    }
+
+    // Rectangle de sélection en cours
+    if let Some(start) = ui_state.rubber_band {
+        let band = Rect::from_two_pos(transform.world_to_screen(start), transform.world_to_screen(ui_state.mouse_pos));
+        painter.rect(band, 0.0, Color32::from_rgba_unmultiplied(255, 255, 0, 24), Stroke::new(1.0, Color32::YELLOW));
     }
 
     // Dessiner la flèche en cours de création (prévisualisation)
-    if let Some(start_point) = &ui_state.arrow_creation_start {
-       if let Some(start_node) = diagram.get_node(start_point.node_id) {
-           let start_pos_world = get_connection_pos(start_node, start_point.side);
+    if let Some(start_end) = &ui_state.arrow_creation_start {
+       if let Some(start_pos_world) = arrow_end_preview_pos(diagram, start_end, ui_state) {
            let start_pos_screen = transform.world_to_screen(start_pos_world);
            // La position de la souris dans ui_state est déjà en monde
            let mouse_pos_screen = transform.world_to_screen(ui_state.mouse_pos);
@@ -48,10 +122,12 @@ pub fn draw_diagram(diagram: &SadtDiagram, transform: &Transform, painter: &Pain
     }
 }
 
-// Dessine un nœud
-fn draw_node(node: &ProcessNode, transform: &Transform, painter: &Painter, is_selected: bool) {
-    let stroke_color = if is_selected { Color32::YELLOW } else { Color32::GRAY };
-    let stroke = Stroke::new(if is_selected { 2.0 } else { 1.0 }, stroke_color);
+// Dessine un nœud et son numéro de boîte
+// (`highlight`: bordure de simulation, activité exécutée ou en erreur)
+fn draw_node(node: &ProcessNode, number: Option<u32>, transform: &Transform, painter: &Painter, is_selected: bool, highlight: Option<Color32>) {
+    let stroke_color = if is_selected { Color32::YELLOW } else { highlight.unwrap_or(NODE_STROKE) };
+    let stroke_width = if is_selected || highlight.is_some() { 2.0 } else { 1.0 };
+    let stroke = Stroke::new(stroke_width, stroke_color);
 
     let screen_rect = transform.world_rect_to_screen(node.rect);
 
@@ -63,26 +139,50 @@ fn draw_node(node: &ProcessNode, transform: &Transform, painter: &Painter, is_se
     let corner_radius_screen: f32 = NODE_CORNER_RADIUS * transform.zoom; // Ajuster rayon et minimum
     let corner_radius_screen = corner_radius_screen.max(1.5_f32);
 
-    painter.rect(screen_rect, corner_radius_screen, Color32::from_gray(50), stroke);
+    // Couleur propre au nœud, texte noir ou blanc pour rester lisible
+    let (fill, text_color) = match node.fill {
+        Some(fill) => (color32(fill), color32(fill.contrasting_text())),
+        None => (NODE_FILL, NODE_TEXT),
+    };
+    painter.rect(screen_rect, corner_radius_screen, fill, stroke);
 
-    // Ajuster taille police et ne dessiner que si assez grand
-    let font_size = 14.0 * transform.zoom.sqrt();
-    if font_size > 5.0 { // Seuil minimum pour dessiner le texte
-        painter.text(
-            screen_rect.center(),
-            Align2::CENTER_CENTER,
-            &node.name,
-            FontId::proportional(font_size),
-            Color32::WHITE,
-        );
+    // Double bordure pour signaler une activité décomposée en sous-diagramme
+    if node.is_decomposed() {
+        let inset = (DECOMPOSED_INSET * transform.zoom).max(1.0);
+        painter.rect_stroke(screen_rect.shrink(inset), corner_radius_screen, Stroke::new(1.0, stroke_color));
     }
 
+    // Nom sur plusieurs lignes, coupé comme dans l'export SVG: la police suit le zoom
+    // pour que le texte reste dans la boîte. Pas de texte si trop petit.
+    let font_size = NODE_FONT_SIZE * transform.zoom;
+    if font_size > 5.0 {
+        let lines = node.label_lines();
+        let line_height = font_size * LINE_HEIGHT;
+        let first_y = screen_rect.center().y - line_height * lines.len().saturating_sub(1) as f32 / 2.0;
+        for (index, line) in lines.iter().enumerate() {
+            painter.text(
+                Pos2::new(screen_rect.center().x, first_y + line_height * index as f32),
+                Align2::CENTER_CENTER,
+                line,
+                FontId::proportional(font_size),
+                text_color,
+            );
+        }
+    }
+
+
+    // Numéro de boîte dans le coin inférieur droit
+    let number_font_size = LABEL_FONT_SIZE * transform.zoom;
+    if let Some(number) = number.filter(|_| number_font_size > 5.0) {
+        let corner = transform.world_to_screen(node.rect.max - WorldVec2::new(BOX_NUMBER_INSET, BOX_NUMBER_INSET));
+        painter.text(corner, Align2::RIGHT_BOTTOM, number.to_string(), FontId::proportional(number_font_size), text_color);
+    }
 
     // Dessiner points de connexion (si assez zoomé)
     let conn_point_radius_screen = CONNECTION_POINT_RADIUS * transform.zoom;
     if conn_point_radius_screen > 1.0 { // Seuil minimum pour dessiner les points
         for side in [Side::Left, Side::Right, Side::Top, Side::Bottom] {
-            let world_pos = get_connection_pos(node, side);
+            let world_pos = node.connection_pos(side);
             let screen_pos = transform.world_to_screen(world_pos);
             // Optimisation simple: ne dessine que si dans le clip_rect
             if painter.clip_rect().contains(screen_pos) {
@@ -93,72 +193,226 @@ fn draw_node(node: &ProcessNode, transform: &Transform, painter: &Painter, is_se
     }
 }
 
-// Dessine une flèche
+// Couleur de base d'une flèche selon son type
+pub fn arrow_color(arrow_type: ArrowType) -> Color32 {
+    color32(style::arrow_color(arrow_type))
+}
+
+// Dessine le cadre du diagramme et les ports ICOM de la boîte parente non encore connectés
+// Cases du cartouche IDEF0, textes masqués quand ils deviennent illisibles
+fn draw_form(form: &Idef0Frame, transform: &Transform, painter: &Painter) {
+    let stroke = Stroke::new(1.0, FRAME_STROKE);
+    painter.rect_stroke(transform.world_rect_to_screen(form.outer), 0.0, stroke);
+    let caption_size = FRAME_CAPTION_FONT_SIZE * transform.zoom;
+    let value_size = FRAME_VALUE_FONT_SIZE * transform.zoom;
+    let readable = caption_size > 4.0;
+    for cell in &form.cells {
+        painter.rect_stroke(transform.world_rect_to_screen(cell.rect), 0.0, stroke);
+        if !readable { continue; }
+        painter.text(transform.world_to_screen(cell.caption_pos()), Align2::LEFT_TOP, cell.caption, FontId::proportional(caption_size), FRAME_STROKE);
+        painter.text(transform.world_to_screen(cell.value_pos()), Align2::LEFT_TOP, &cell.value, FontId::proportional(value_size), NODE_TEXT);
+    }
+    for mark in &form.marks {
+        let square = transform.world_rect_to_screen(mark.rect);
+        if mark.checked {
+            painter.rect_filled(square, 0.0, NODE_TEXT);
+        }
+        painter.rect_stroke(square, 0.0, stroke);
+        if readable {
+            painter.text(transform.world_to_screen(mark.label_pos()), Align2::LEFT_TOP, mark.label, FontId::proportional(caption_size), NODE_TEXT);
+        }
+    }
+}
+
+fn draw_frame_and_ports(diagram: &SadtDiagram, transform: &Transform, painter: &Painter, ui_state: &UiState) {
+    let frame = diagram.frame();
+    painter.rect_stroke(transform.world_rect_to_screen(frame), 0.0, Stroke::new(1.0, FRAME_STROKE));
+
+    let connected: Vec<IcomCode> = diagram.boundary_arrows().filter_map(|a| a.icom()).collect();
+    let font_size: f32 = (LABEL_FONT_SIZE * transform.zoom.sqrt()).max(6.0);
+    for port in &ui_state.boundary_ports {
+        if connected.contains(&port.code) { continue; }
+        let color = arrow_color(port.code.arrow_type);
+        let port_pos = boundary_port_pos(frame, &ui_state.boundary_ports, port.code);
+        let inner_pos = port_pos + inward_dir(port.code.side()) * BOUNDARY_STUB_LENGTH;
+        let (from, to) = if port.code.arrow_type == ArrowType::Output { (inner_pos, port_pos) } else { (port_pos, inner_pos) };
+        let (from, to) = (transform.world_to_screen(from), transform.world_to_screen(to));
+        painter.line_segment([from, to], Stroke::new(ARROW_STROKE_WIDTH, color));
+        draw_arrow_head(painter, to, from, (ARROW_HEAD_SIZE * transform.zoom.sqrt()).max(3.0), color);
+        painter.circle_stroke(transform.world_to_screen(port_pos), (CONNECTION_POINT_RADIUS * transform.zoom).max(1.0), Stroke::new(1.0, color));
+        let text = match &port.label { Some(label) => format!("{} {}", port.code, label), None => port.code.to_string() };
+        draw_icom_label(painter, transform, port_pos, port.code, &text, font_size, color);
+    }
+}
+
+// Texte d'un code ICOM placé à l'extérieur du cadre, près de l'extrémité ouverte
+fn draw_icom_label(painter: &Painter, transform: &Transform, world_pos: Point, code: IcomCode, text: &str, font_size: f32, color: Color32) {
+    let (offset, align) = match code.side() {
+        Side::Left => (vec2(-ICOM_LABEL_OFFSET, 0.0), Align2::RIGHT_CENTER),
+        Side::Right => (vec2(ICOM_LABEL_OFFSET, 0.0), Align2::LEFT_CENTER),
+        Side::Top => (vec2(0.0, -ICOM_LABEL_OFFSET), Align2::CENTER_BOTTOM),
+        Side::Bottom => (vec2(0.0, ICOM_LABEL_OFFSET), Align2::CENTER_TOP),
+    };
+    painter.text(transform.world_to_screen(world_pos) + offset, align, text, FontId::proportional(font_size), color);
+}
+
+// Grille de fond: une ligne sur cinq plus marquée; quand les lignes deviennent trop
+// serrées à l'écran, seule une sur cinq est gardée
+fn draw_grid(transform: &Transform, painter: &Painter, grid: &GridSettings) {
+    let mut step = grid.spacing;
+    while step * transform.zoom < MIN_GRID_STEP { step *= 5.0; }
+    let clip = painter.clip_rect();
+    let (min, max) = (transform.screen_to_world(clip.min), transform.screen_to_world(clip.max));
+    let color = |value: f32| if ((value / step).round() as i64).rem_euclid(5) == 0 { GRID_MAJOR } else { GRID_MINOR };
+
+    let mut x = (min.x / step).floor() * step;
+    while x <= max.x {
+        let line = [transform.world_to_screen(Point::new(x, min.y)), transform.world_to_screen(Point::new(x, max.y))];
+        painter.line_segment(line, Stroke::new(1.0, color(x)));
+        x += step;
+    }
+    let mut y = (min.y / step).floor() * step;
+    while y <= max.y {
+        let line = [transform.world_to_screen(Point::new(min.x, y)), transform.world_to_screen(Point::new(max.x, y))];
+        painter.line_segment(line, Stroke::new(1.0, color(y)));
+        y += step;
+    }
+}
+
+// Coins d'un nœud, où se trouvent ses poignées de redimensionnement
+pub fn node_corners(rect: WorldRect) -> [Point; 4] {
+    [rect.min, Point::new(rect.max.x, rect.min.y), rect.max, Point::new(rect.min.x, rect.max.y)]
+}
+
+// Dessine une flèche (tracé orthogonal, voir `SadtDiagram::arrow_path`)
 fn draw_arrow(arrow: &Arrow, diagram: &SadtDiagram, transform: &Transform, painter: &Painter, ui_state: &UiState) {
-    if let (Some(source_node), Some(target_node)) = (
-        diagram.nodes.get(&arrow.source.node_id),
-        diagram.nodes.get(&arrow.target.node_id),
-    ) {
-        let start_pos_world = get_connection_pos(source_node, arrow.source.side);
-        let end_pos_world = get_connection_pos(target_node, arrow.target.side);
-        let start_pos_screen = transform.world_to_screen(start_pos_world);
-        let end_pos_screen = transform.world_to_screen(end_pos_world);
-
-        // Optimisation: vérifier si la ligne croise le rectangle visible
-        let line_rect = Rect::from_two_pos(start_pos_screen, end_pos_screen);
-         if !painter.clip_rect().intersects(line_rect) {
-             // Si les deux points sont hors écran du même côté, on peut skipper.
-             // Un test plus fin serait nécessaire pour les longues lignes traversantes.
-             // Pour l'instant, on dessine si l'un ou l'autre est visible ou si le rect englobant intersecte.
-             // return; // Peut cacher des flèches longues, attention.
-         }
+    let Some(path) = diagram.arrow_path(arrow) else {
+        log::warn!("Impossible de dessiner flèche {}: nœud source/cible manquant.", arrow.id);
+        return;
+    };
+    let points: Vec<Pos2> = path.iter().map(|p| transform.world_to_screen(*p)).collect();
+    let [.., before_tip, tip] = points[..] else { return; };
+
+    let base_color = arrow_color(arrow.arrow_type);
+    let is_selected = ui_state.selection.contains_arrow(arrow.id);
+    let stroke_width = if is_selected { 3.0 } else { ARROW_STROKE_WIDTH }; // Epaisseur écran fixe
+    let color = if is_selected { Color32::YELLOW } else { base_color };
+    let stroke = Stroke::new(stroke_width, color);
+
+    painter.add(egui::Shape::line(points.clone(), stroke));
+
+    // Dessiner tête de flèche (taille ajustée au zoom); une jonction se fond dans le faisceau
+    let head_size: f32 = ARROW_HEAD_SIZE * transform.zoom.sqrt();
+    let head_size = head_size.max(3.0_f32); // Min 3px
+    if arrow.target.junction().is_none() {
+        draw_arrow_head(painter, tip, before_tip, head_size, color);
+    }
+
+    // Point d'embranchement ou de jonction sur l'autre tracé
+    let junction_radius = (JUNCTION_RADIUS * transform.zoom.sqrt()).max(2.0);
+    for (end, pos) in [(&arrow.source, points[0]), (&arrow.target, tip)] {
+        if end.junction().is_some() {
+            painter.circle_filled(pos, junction_radius, color);
+        }
+    }
 
-        let base_color = match arrow.arrow_type {
-            ArrowType::Input => Color32::LIGHT_GREEN, ArrowType::Output => Color32::LIGHT_BLUE,
-            ArrowType::Control => Color32::LIGHT_RED, ArrowType::Mechanism => Color32::LIGHT_YELLOW,
-        };
-        let is_selected = ui_state.selected_arrow == Some(arrow.id);
-        let stroke_width = if is_selected { 3.0 } else { 1.5 }; // Epaisseur écran fixe
-        let color = if is_selected { Color32::YELLOW } else { base_color };
-        let stroke = Stroke::new(stroke_width, color);
-
-        painter.line_segment([start_pos_screen, end_pos_screen], stroke);
-
-        // Dessiner tête de flèche (taille ajustée au zoom)
-        let head_size: f32 = ARROW_HEAD_SIZE * transform.zoom.sqrt();
-        let head_size = head_size.max(3.0_f32); // Min 3px
-        draw_arrow_head(painter, end_pos_screen, start_pos_screen, head_size, color);
-
-        // Dessiner label (si assez zoomé et pas en cours d'édition)
-        let font_size: f32 = 10.0 * transform.zoom.sqrt();
-        let font_size = font_size.max(6.0_f32); // Min 6px
-        if font_size > 6.0 && ui_state.renaming_arrow != Some(arrow.id) {
-            if let Some(label) = &arrow.label {
-                let mid_screen_pos = start_pos_screen.lerp(end_pos_screen, 0.5);
-                 // Petit décalage pour ne pas être pile sur la ligne
-                 let text_pos = mid_screen_pos + vec2(0.0, -stroke_width - 2.0);
-                 painter.text(text_pos, Align2::CENTER_CENTER, label, FontId::proportional(font_size), color);
+    // Points de passage de la flèche sélectionnée, déplaçables à la souris
+    if ui_state.selection.single_arrow() == Some(arrow.id) {
+        for waypoint in &arrow.waypoints {
+            let handle = Rect::from_center_size(transform.world_to_screen(*waypoint), Vec2::splat(WAYPOINT_HANDLE_SIZE));
+            painter.rect(handle, 0.0, Color32::DARK_GRAY, Stroke::new(1.0, Color32::YELLOW));
+        }
+        // Extrémités sur une boîte: à faire glisser le long du côté pour réordonner
+        for (end, pos) in [(&arrow.source, path[0]), (&arrow.target, path[path.len() - 1])] {
+            if end.connection_point().is_some() {
+                painter.circle(transform.world_to_screen(pos), WAYPOINT_HANDLE_SIZE / 2.0, Color32::DARK_GRAY, Stroke::new(1.0, Color32::YELLOW));
             }
         }
-    } else {
-        log::warn!("Impossible de dessiner flèche {}: nœud source/cible manquant.", arrow.id);
+    }
+
+    // Dessiner label (si assez zoomé et pas en cours d'édition)
+    let font_size: f32 = LABEL_FONT_SIZE * transform.zoom.sqrt();
+    let font_size = font_size.max(6.0_f32); // Min 6px
+    // Pendant une simulation, la valeur courante suit le label
+    let value = ui_state.simulation.as_ref().and_then(|sim| sim.values.get(&arrow.id));
+    let label = match (&arrow.label, value) {
+        (Some(label), Some(value)) => Some(format!("{} = {}", label, value)),
+        (None, Some(value)) => Some(format!("= {}", value)),
+        (label, None) => label.clone(),
+    };
+    if font_size > 6.0 && ui_state.renaming_arrow != Some(arrow.id) {
+        if let Some(label) = &label {
+            let mid_screen_pos = transform.world_to_screen(path_midpoint(&path));
+             // Petit décalage pour ne pas être pile sur la ligne
+             let text_pos = mid_screen_pos + vec2(0.0, -stroke_width - 2.0);
+             painter.text(text_pos, Align2::CENTER_CENTER, label, FontId::proportional(font_size), color);
+        }
+    }
+
+    // Code ICOM à l'extrémité ouverte d'une flèche frontière
+    if let Some(code) = arrow.icom() {
+        let open_end = if arrow.source.icom().is_some() { path[0] } else { path[path.len() - 1] };
+        draw_icom_label(painter, transform, open_end, code, &code.to_string(), font_size, color);
     }
 }
 
-// Calcule la position monde d'un point de connexion
-pub fn get_connection_pos(node: &ProcessNode, side: Side) -> Pos2 {
+// Vecteur unitaire pointant du côté du cadre vers l'intérieur du diagramme
+fn inward_dir(side: Side) -> WorldVec2 {
     match side {
-        Side::Left => Pos2::new(node.rect.left(), node.rect.center().y),
-        Side::Right => Pos2::new(node.rect.right(), node.rect.center().y),
-        Side::Top => Pos2::new(node.rect.center().x, node.rect.top()),
-        Side::Bottom => Pos2::new(node.rect.center().x, node.rect.bottom()),
+        Side::Left => WorldVec2::new(1.0, 0.0),
+        Side::Right => WorldVec2::new(-1.0, 0.0),
+        Side::Top => WorldVec2::new(0.0, 1.0),
+        Side::Bottom => WorldVec2::new(0.0, -1.0),
     }
 }
 
+// Position d'un port ICOM du parent, les codes d'un même côté étant répartis régulièrement
+pub fn boundary_port_pos(frame: WorldRect, ports: &[ParentIcom], code: IcomCode) -> Point {
+    let count = ports.iter().filter(|p| p.code.arrow_type == code.arrow_type).count().max(code.index as usize);
+    let t = code.index as f32 / (count as f32 + 1.0);
+    match code.side() {
+        Side::Left => Point::new(frame.left(), frame.top() + frame.height() * t),
+        Side::Right => Point::new(frame.right(), frame.top() + frame.height() * t),
+        Side::Top => Point::new(frame.left() + frame.width() * t, frame.top()),
+        Side::Bottom => Point::new(frame.left() + frame.width() * t, frame.bottom()),
+    }
+}
+
+// Position de départ de la flèche en cours de création
+fn arrow_end_preview_pos(diagram: &SadtDiagram, end: &ArrowEnd, ui_state: &UiState) -> Option<Point> {
+    match end {
+        ArrowEnd::Node(cp) => diagram.get_node(cp.node_id).map(|node| node.connection_pos(cp.side)),
+        ArrowEnd::Boundary(code) => Some(boundary_port_pos(diagram.frame(), &ui_state.boundary_ports, *code)),
+        // Embranchement: glisse le long du tracé en suivant la souris
+        ArrowEnd::Junction(id) => {
+            let path = diagram.arrow_path(diagram.get_arrow(*id)?)?;
+            let obstacles: Vec<WorldRect> = diagram.nodes.values().map(|node| node.rect).collect();
+            junction_point(&path, ui_state.mouse_pos, &obstacles).map(|(pos, _)| pos)
+        }
+    }
+}
+
+// Trouve le port ICOM non connecté le plus proche (en coordonnées monde)
+pub fn find_closest_boundary_port(
+    diagram: &SadtDiagram,
+    ports: &[ParentIcom],
+    world_pos: Point,
+    max_dist_world: f32,
+) -> Option<IcomCode> {
+    let frame = diagram.frame();
+    let connected: Vec<IcomCode> = diagram.boundary_arrows().filter_map(|a| a.icom()).collect();
+    ports.iter()
+        .filter(|port| !connected.contains(&port.code))
+        .map(|port| (port.code, boundary_port_pos(frame, ports, port.code).distance_sq(world_pos)))
+        .filter(|(_, dist_sq)| *dist_sq < max_dist_world * max_dist_world)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(code, _)| code)
+}
+
 // Trouve le point de connexion le plus proche (en coordonnées monde)
 pub fn find_closest_connection_point(
     diagram: &SadtDiagram,
-    world_pos: Pos2,
+    world_pos: Point,
     max_dist_world: f32, // Comparaison en distance monde
 ) -> Option<ConnectionPoint> {
     let max_dist_sq = max_dist_world * max_dist_world;
@@ -167,11 +421,11 @@ pub fn find_closest_connection_point(
 
     for node in diagram.nodes.values() {
          for side in [Side::Left, Side::Right, Side::Top, Side::Bottom] {
-            let conn_pos_world = get_connection_pos(node, side);
+            let conn_pos_world = node.connection_pos(side);
             let dist_sq = conn_pos_world.distance_sq(world_pos);
             if dist_sq < min_dist_sq {
                 min_dist_sq = dist_sq;
-                closest_point = Some(ConnectionPoint { node_id: node.id, side });
+                closest_point = Some(ConnectionPoint::new(node.id, side));
             }
          }
     }
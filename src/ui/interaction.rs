@@ -1,21 +1,18 @@
 use crate::app::AppState;
-use crate::sadt_elements::{ArrowType, Side};
-use crate::ui::drawing::{find_closest_connection_point, get_connection_pos, CONNECTION_POINT_RADIUS};
+use rustsadt_core::layout::{align_nodes, distribute_nodes, Alignment};
+use rustsadt_core::sadt_model::SadtDiagram;
+use rustsadt_core::sadt_elements::{ArrowEnd, ArrowId, ArrowType, NodeId, Side};
+use crate::ui::drawing::{find_closest_boundary_port, find_closest_connection_point, node_corners, CONNECTION_POINT_RADIUS, RESIZE_HANDLE_SIZE, WAYPOINT_HANDLE_SIZE};
+use crate::ui::clipboard::{copy_selection, cut_selection, duplicate_selection, paste, paste_text};
+use crate::ui::selection::Selection;
+use crate::ui::snapping::snap_moved_rect;
 use crate::ui::transform::Transform; // Importer Transform
-use egui::{vec2, Context, Key, PointerButton, Pos2, Ui, Response};
+use egui::{vec2, Context, Key, Modifiers, PointerButton, Ui, Response};
+use rustsadt_core::geometry::{Point, Rect as WorldRect, Vec2 as WorldVec2};
+use rustsadt_core::routing::{distance_sq_to_path, path_midpoint};
 
 const ARROW_SELECT_DISTANCE: f32 = 5.0; // Tolérance écran pour sélectionner une flèche
 
-// Helper: Calcule la distance² d'un point à un segment de ligne (en coordonnées monde)
-fn distance_sq_to_segment(p: Pos2, a: Pos2, b: Pos2) -> f32 {
-    let l2 = a.distance_sq(b);
-    if l2 == 0.0 { return p.distance_sq(a); }
-    let t = ((p.x - a.x) * (b.x - a.x) + (p.y - a.y) * (b.y - a.y)) / l2;
-    let t = t.clamp(0.0, 1.0);
-    let projection = a + (b - a) * t;
-    p.distance_sq(projection)
-}
-
 // Fonction principale appelée depuis app.rs
 pub fn handle_canvas_interactions(
     ctx: &Context,
@@ -29,15 +26,25 @@ pub fn handle_canvas_interactions(
         app_state.ui_state.mouse_pos = transform.screen_to_world(screen_pos_mouse);
     }
 
+    if response.secondary_clicked() {
+        app_state.ui_state.context_menu_pos = app_state.ui_state.mouse_pos;
+    }
+
+    handle_waypoint_drag(ctx, response, transform, app_state);
+    handle_slot_drag(ctx, response, transform, app_state);
+    handle_node_resize(ctx, response, transform, app_state);
     handle_arrow_selection(ctx, response, transform, app_state);
     handle_node_drag_and_select(ctx, response, transform, app_state);
+    handle_drill_down(response, transform, app_state);
     handle_arrow_creation(ctx, response, transform, app_state);
+    handle_rubber_band(ctx, response, transform, app_state);
     handle_rename(ctx, transform, app_state); // Passe transform pour positionnement fenêtre
     handle_deletion(ctx, app_state);
+    handle_clipboard(ctx, app_state);
 
     // --- Menu Contextuel (utilise transform pour position ajout nœud) ---
     response.context_menu(|ui| {
-        if let Some(node_id) = app_state.ui_state.selected_node {
+        if let Some(node_id) = app_state.ui_state.selection.single_node() {
              if ui.button("Renommer Nœud").clicked() {
                  app_state.ui_state.renaming_node = Some(node_id);
                  if let Some(node) = app_state.diagram.get_node(node_id) {
@@ -45,14 +52,25 @@ pub fn handle_canvas_interactions(
                  } else { app_state.ui_state.renaming_label_text = String::new(); }
                  app_state.ui_state.renaming_arrow = None; ui.close_menu();
             }
+            if ui.button("Ajuster la taille au nom").clicked() {
+                app_state.edit("Ajuster taille", |diagram| fit_nodes_to_labels(diagram, &[node_id]));
+                ui.close_menu();
+            }
+            let is_decomposed = app_state.diagram.get_node(node_id).is_some_and(|n| n.is_decomposed());
+            if is_decomposed && ui.button("Ouvrir le sous-diagramme").clicked() {
+                app_state.enter_child_diagram(node_id); ui.close_menu();
+            }
+            if !is_decomposed && ui.button("Décomposer").clicked() {
+                app_state.decompose(node_id); ui.close_menu();
+            }
             ui.separator();
+            clipboard_menu(ctx, ui, app_state);
             if ui.button("Supprimer Nœud").clicked() {
-                if let Some(id) = app_state.ui_state.selected_node.take() {
-                    app_state.diagram.remove_node(id); log::info!("Nœud {} supprimé via menu", id);
-                } ui.close_menu();
+                delete_selection(app_state); log::info!("Nœud {} supprimé via menu", node_id);
+                ui.close_menu();
             }
         }
-        else if let Some(arrow_id) = app_state.ui_state.selected_arrow {
+        else if let Some(arrow_id) = app_state.ui_state.selection.single_arrow() {
             if ui.button("Editer Label Flèche").clicked() {
                 app_state.ui_state.renaming_arrow = Some(arrow_id);
                  if let Some(arrow) = app_state.diagram.get_arrow(arrow_id) {
@@ -60,21 +78,87 @@ pub fn handle_canvas_interactions(
                  } else { app_state.ui_state.renaming_label_text = String::new(); }
                 app_state.ui_state.renaming_node = None; ui.close_menu();
             }
+            ui.menu_button("Type de donnée", |ui| {
+                let current = app_state.diagram.get_arrow(arrow_id).and_then(|a| a.data_type.clone());
+                let mut choices = vec![None];
+                choices.extend(app_state.root_dictionary().types.iter().map(|t| Some(t.name.clone())));
+                // Type inconnu du dictionnaire: reste affiché pour pouvoir le retirer
+                if current.is_some() && !choices.contains(&current) { choices.push(current.clone()); }
+                for choice in choices {
+                    let text = choice.clone().unwrap_or_else(|| "(aucun)".to_string());
+                    if ui.radio(current == choice, text).clicked() {
+                        app_state.edit("Typer flèche", |diagram| {
+                            if let Some(a) = diagram.arrows.get_mut(&arrow_id) { a.data_type = choice; }
+                        });
+                        ui.close_menu();
+                    }
+                }
+                if app_state.root_dictionary().is_empty() {
+                    ui.label("Dictionnaire vide (Edition > Dictionnaire de données...)");
+                }
+            });
+            ui.menu_button("Tracé", |ui| {
+                let pos = app_state.ui_state.context_menu_pos;
+                let waypoint = waypoint_at(app_state, arrow_id, pos, transform);
+                let has_waypoints = app_state.diagram.get_arrow(arrow_id).is_some_and(|a| !a.waypoints.is_empty());
+                if ui.button("Ajouter un point de passage").clicked() {
+                    app_state.edit("Ajouter point de passage", |diagram| diagram.add_waypoint(arrow_id, pos));
+                    ui.close_menu();
+                }
+                if ui.add_enabled(waypoint.is_some(), egui::Button::new("Supprimer ce point de passage")).clicked() {
+                    if let Some(index) = waypoint {
+                        app_state.edit("Supprimer point de passage", |diagram| {
+                            if let Some(a) = diagram.arrows.get_mut(&arrow_id) { a.waypoints.remove(index); }
+                        });
+                    }
+                    ui.close_menu();
+                }
+                if ui.add_enabled(!has_waypoints, egui::Button::new("Figer le tracé")).clicked() {
+                    app_state.edit("Figer tracé", |diagram| diagram.freeze_arrow_path(arrow_id));
+                    ui.close_menu();
+                }
+                if ui.add_enabled(has_waypoints, egui::Button::new("Tracé automatique")).clicked() {
+                    app_state.edit("Tracé automatique", |diagram| {
+                        if let Some(a) = diagram.arrows.get_mut(&arrow_id) { a.waypoints.clear(); }
+                    });
+                    ui.close_menu();
+                }
+                ui.separator();
+                ui.label("Alt+glisser depuis la flèche: embranchement");
+                ui.label("Relâcher une flèche sur celle-ci: jonction");
+            });
              ui.separator();
             if ui.button("Supprimer Flèche").clicked() {
-                 if let Some(id) = app_state.ui_state.selected_arrow.take() {
-                    app_state.diagram.remove_arrow(id); log::info!("Flèche {} supprimée via menu", id);
-                 } ui.close_menu();
+                delete_selection(app_state); log::info!("Flèche {} supprimée via menu", arrow_id);
+                ui.close_menu();
+            }
+        } else if !app_state.ui_state.selection.is_empty() {
+            ui.menu_button("Aligner", |ui| alignment_menu(ui, app_state));
+            let nodes = app_state.ui_state.selection.nodes.clone();
+            if ui.add_enabled(!nodes.is_empty(), egui::Button::new("Ajuster la taille au nom")).clicked() {
+                app_state.edit("Ajuster taille", |diagram| fit_nodes_to_labels(diagram, &nodes));
+                ui.close_menu();
+            }
+            ui.separator();
+            clipboard_menu(ctx, ui, app_state);
+            if ui.button("Supprimer la sélection").clicked() {
+                delete_selection(app_state);
+                ui.close_menu();
             }
         } else {
             if ui.button("Ajouter Nœud").clicked() {
                 let screen_pos = ctx.input(|i| i.pointer.interact_pos()).unwrap_or_else(|| response.rect.center());
-                let world_pos = transform.screen_to_world(screen_pos);
+                let mut world_pos = transform.screen_to_world(screen_pos);
+                if app_state.grid.snap { world_pos = app_state.grid.snap_point(world_pos); }
                  let node_name = format!("Activité {}", app_state.diagram.nodes.len() + 1);
-                app_state.diagram.add_node(node_name, world_pos);
+                app_state.edit("Ajouter nœud", |diagram| diagram.add_node(node_name, world_pos));
                  log::info!("Nœud ajouté via menu contextuel à monde {:?}", world_pos);
                  ui.close_menu();
             }
+            if ui.add_enabled(app_state.clipboard.text.is_some(), egui::Button::new("Coller").shortcut_text("Ctrl+V")).clicked() {
+                paste(app_state);
+                ui.close_menu();
+            }
         }
     });
 }
@@ -90,43 +174,205 @@ fn handle_node_drag_and_select(ctx: &Context, response: &Response, transform: &T
         let clicked_on_connection_point = find_closest_connection_point(
             &app_state.diagram, click_pos_world, CONNECTION_POINT_RADIUS * 3.0).is_some();
 
-        let mut clicked_on_node = None;
-        if !clicked_on_connection_point {
-            for node in app_state.diagram.nodes.values() {
-                if node.rect.contains(click_pos_world) {
-                    clicked_on_node = Some(node.id); break;
-                }
-            }
-        }
+        let clicked_on_node = if clicked_on_connection_point { None } else { node_at(app_state, click_pos_world) };
 
         if let Some(node_id) = clicked_on_node {
-             if app_state.ui_state.arrow_creation_start.is_none() {
-                app_state.ui_state.selected_node = Some(node_id);
-                app_state.ui_state.selected_arrow = None;
-                 ctx.memory_mut(|mem| mem.set_dragged_id(response.id)); // Marquer pour drag du canvas
-                 log::trace!("Nœud {} sélectionné pour drag potentiel", node_id);
+             if app_state.ui_state.dragging_waypoint.is_some() || app_state.ui_state.dragging_slot.is_some()
+                || app_state.ui_state.resizing_node.is_some()
+             {
+                log::trace!("Clic sur nœud ignoré (point de passage, extrémité ou redimensionnement en cours)");
+             } else if app_state.ui_state.arrow_creation_start.is_none() {
+                let selection = &mut app_state.ui_state.selection;
+                if ctx.input(|i| i.modifiers.shift) {
+                    selection.toggle_node(node_id);
+                } else if !selection.contains_node(node_id) {
+                    *selection = Selection::node(node_id);
+                }
+                // Un clic sur un nœud de la sélection déplace tout le groupe
+                if app_state.ui_state.selection.contains_node(node_id) {
+                    app_state.ui_state.dragging_nodes = true;
+                    app_state.begin_edit(); // Tout le drag formera une seule étape d'annulation
+                    ctx.set_dragged_id(response.id); // Marquer pour drag du canvas
+                    log::trace!("Nœud {} sélectionné pour drag potentiel", node_id);
+                }
              } else { log::trace!("Clic sur nœud ignoré (création flèche en cours)"); }
         } else { log::trace!("Clic ni sur nœud ni sur point connexion (pour sélection nœud)"); }
     }
 
-    if pointer.button_down(PointerButton::Primary) && ctx.is_being_dragged(response.id) && app_state.ui_state.arrow_creation_start.is_none() {
-        if let Some(node_id) = app_state.ui_state.selected_node {
-            let delta_world = transform.screen_vec_to_world(pointer.delta());
-            if delta_world.length_sq() > 0.0 { // Seulement si mouvement réel
-                if let Some(node) = app_state.diagram.get_node_mut(node_id) {
-                    node.rect = node.rect.translate(delta_world);
-                    log::trace!("Dragging node {} par monde {:?}", node_id, delta_world);
-                }
+    if pointer.button_down(PointerButton::Primary) && app_state.ui_state.dragging_nodes && app_state.ui_state.arrow_creation_start.is_none() {
+        // Position recalculée depuis le début du geste (diagramme d'avant le drag),
+        // pour que l'accrochage ne dépende pas des déplacements déjà arrondis
+        let ids = app_state.ui_state.selection.nodes.clone();
+        let bounds = |diagram: &SadtDiagram| ids.iter()
+            .filter_map(|id| diagram.get_node(*id))
+            .fold(WorldRect::NOTHING, |acc, node| acc.union(node.rect));
+        if let (Some(before), Some(press_origin)) = (&app_state.pending_edit, pointer.press_origin()) {
+            let proposed = bounds(before).translate(app_state.ui_state.mouse_pos - transform.screen_to_world(press_origin));
+            let (correction, guides) = if ctx.input(|i| i.modifiers.alt) {
+                (WorldVec2::ZERO, Vec::new())
+            } else {
+                let others: Vec<WorldRect> = app_state.diagram.nodes.values()
+                    .filter(|node| !ids.contains(&node.id))
+                    .map(|node| node.rect)
+                    .collect();
+                snap_moved_rect(proposed, &others, &app_state.grid, transform.zoom)
+            };
+            let step = (proposed.min + correction) - bounds(&app_state.diagram).min;
+            if step.length_sq() > 0.0 { // Seulement si mouvement réel
+                app_state.diagram.translate_nodes(&ids, step);
+                log::trace!("Dragging {} nœud(s) par monde {:?}", ids.len(), step);
             }
+            app_state.ui_state.guides = guides;
         }
     }
 
-    if pointer.any_released() && ctx.is_being_dragged(response.id) {
+    if pointer.any_released() && std::mem::take(&mut app_state.ui_state.dragging_nodes) {
+        app_state.ui_state.guides.clear();
+        let label = if app_state.ui_state.selection.nodes.len() > 1 { "Déplacer la sélection" } else { "Déplacer nœud" };
+        app_state.end_edit(label);
         // Ne pas appeler stop_dragging() ici car on pourrait vouloir continuer le pan
         // ctx.stop_dragging(); // Retiré
     }
 }
 
+// Double-clic sur un nœud décomposé: ouvre son sous-diagramme (la décomposition
+// se crée depuis le menu contextuel)
+fn handle_drill_down(response: &Response, transform: &Transform, app_state: &mut AppState) {
+    if !response.double_clicked() { return; }
+    let Some(click_pos_screen) = response.interact_pointer_pos() else { return; };
+    let click_pos_world = transform.screen_to_world(click_pos_screen);
+    let clicked_node = app_state.diagram.nodes.values()
+        .find(|node| node.is_decomposed() && node.rect.contains(click_pos_world))
+        .map(|node| node.id);
+    if let Some(node_id) = clicked_node {
+        app_state.enter_child_diagram(node_id);
+    }
+}
+
+// Point de passage de la flèche dont la poignée est sous `world_pos`
+fn waypoint_at(app_state: &AppState, arrow_id: ArrowId, world_pos: Point, transform: &Transform) -> Option<usize> {
+    let max_dist = WAYPOINT_HANDLE_SIZE / transform.zoom;
+    app_state.diagram.get_arrow(arrow_id)?.waypoints.iter()
+        .position(|waypoint| waypoint.distance(world_pos) <= max_dist)
+}
+
+// Déplacement à la souris des points de passage de la flèche sélectionnée
+// (un déplacement forme une seule étape d'annulation)
+fn handle_waypoint_drag(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
+    let pointer = &ctx.input(|i| i.pointer.clone());
+
+    if response.hovered() && pointer.button_pressed(PointerButton::Primary) && app_state.ui_state.arrow_creation_start.is_none() {
+        let Some(arrow_id) = app_state.ui_state.selection.single_arrow() else { return; };
+        let press_pos_world = transform.screen_to_world(pointer.press_origin().unwrap_or_default());
+        if let Some(index) = waypoint_at(app_state, arrow_id, press_pos_world, transform) {
+            app_state.ui_state.dragging_waypoint = Some((arrow_id, index));
+            app_state.begin_edit();
+        }
+    }
+
+    if let Some((arrow_id, index)) = app_state.ui_state.dragging_waypoint {
+        if pointer.button_down(PointerButton::Primary) {
+            let delta_world = transform.screen_vec_to_world(pointer.delta());
+            if let Some(waypoint) = app_state.diagram.arrows.get_mut(&arrow_id).and_then(|a| a.waypoints.get_mut(index)) {
+                *waypoint += delta_world;
+            }
+        }
+        if pointer.any_released() {
+            app_state.ui_state.dragging_waypoint = None;
+            app_state.end_edit("Déplacer point de passage");
+        }
+    }
+}
+
+// Extrémité sur une boîte de la flèche dont la poignée est sous `world_pos`
+// (`true` pour la source, `false` pour la cible)
+fn slot_at(app_state: &AppState, arrow_id: ArrowId, world_pos: Point, transform: &Transform) -> Option<bool> {
+    let max_dist = WAYPOINT_HANDLE_SIZE / transform.zoom;
+    let arrow = app_state.diagram.get_arrow(arrow_id)?;
+    [(true, &arrow.source), (false, &arrow.target)].into_iter().find_map(|(is_source, end)| {
+        let pos = app_state.diagram.slot_pos(end.connection_point()?, arrow_id)?;
+        (pos.distance(world_pos) <= max_dist).then_some(is_source)
+    })
+}
+
+// Réordonne les flèches d'un côté en faisant glisser l'extrémité de la flèche
+// sélectionnée le long du côté (un déplacement forme une seule étape d'annulation)
+fn handle_slot_drag(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
+    let pointer = &ctx.input(|i| i.pointer.clone());
+
+    if response.hovered() && pointer.button_pressed(PointerButton::Primary)
+        && app_state.ui_state.arrow_creation_start.is_none() && app_state.ui_state.dragging_waypoint.is_none()
+    {
+        let Some(arrow_id) = app_state.ui_state.selection.single_arrow() else { return; };
+        let press_pos_world = transform.screen_to_world(pointer.press_origin().unwrap_or_default());
+        if let Some(is_source) = slot_at(app_state, arrow_id, press_pos_world, transform) {
+            app_state.ui_state.dragging_slot = Some((arrow_id, is_source));
+            app_state.begin_edit();
+        }
+    }
+
+    if let Some((arrow_id, is_source)) = app_state.ui_state.dragging_slot {
+        if pointer.button_down(PointerButton::Primary) {
+            let mouse_pos = app_state.ui_state.mouse_pos;
+            let end = app_state.diagram.get_arrow(arrow_id)
+                .and_then(|a| if is_source { a.source.connection_point() } else { a.target.connection_point() })
+                .cloned();
+            if let Some(rank) = end.and_then(|cp| app_state.diagram.slot_rank_at(cp.node_id, cp.side, mouse_pos)) {
+                app_state.diagram.move_to_slot(arrow_id, is_source, rank);
+            }
+        }
+        if pointer.any_released() {
+            app_state.ui_state.dragging_slot = None;
+            app_state.end_edit("Réordonner flèche");
+        }
+    }
+}
+
+// Redimensionnement du nœud sélectionné par ses coins: le coin opposé reste fixe.
+// Le coin déplacé s'accroche à la grille si le magnétisme est actif (sauf avec Alt);
+// la taille ne descend pas sous celle où le nom tient dans la boîte.
+fn handle_node_resize(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
+    let pointer = &ctx.input(|i| i.pointer.clone());
+
+    if response.hovered() && pointer.button_pressed(PointerButton::Primary)
+        && app_state.ui_state.arrow_creation_start.is_none() && app_state.ui_state.dragging_waypoint.is_none()
+        && app_state.ui_state.dragging_slot.is_none()
+    {
+        let Some(node) = app_state.ui_state.selection.single_node().and_then(|id| app_state.diagram.get_node(id)) else { return; };
+        let press_pos_world = transform.screen_to_world(pointer.press_origin().unwrap_or_default());
+        let max_dist = RESIZE_HANDLE_SIZE / transform.zoom;
+        let rect = node.rect;
+        if let Some(corner) = node_corners(rect).into_iter().find(|corner| corner.distance(press_pos_world) <= max_dist) {
+            let anchor = Point::new(rect.min.x + rect.max.x - corner.x, rect.min.y + rect.max.y - corner.y);
+            app_state.ui_state.resizing_node = Some((node.id, anchor));
+            app_state.begin_edit();
+        }
+    }
+
+    if let Some((node_id, anchor)) = app_state.ui_state.resizing_node {
+        if pointer.button_down(PointerButton::Primary) {
+            let mut mouse_pos = app_state.ui_state.mouse_pos;
+            if app_state.grid.snap && !ctx.input(|i| i.modifiers.alt) {
+                mouse_pos = app_state.grid.snap_point(mouse_pos);
+            }
+            if let Some(node) = app_state.diagram.get_node_mut(node_id) {
+                let size = WorldVec2::new((mouse_pos.x - anchor.x).abs(), (mouse_pos.y - anchor.y).abs());
+                let min = node.min_size(size.x);
+                let size = WorldVec2::new(size.x.max(min.x), size.y.max(min.y));
+                let corner = Point::new(
+                    if mouse_pos.x < anchor.x { anchor.x - size.x } else { anchor.x + size.x },
+                    if mouse_pos.y < anchor.y { anchor.y - size.y } else { anchor.y + size.y },
+                );
+                node.rect = WorldRect::from_two_pos(anchor, corner);
+            }
+        }
+        if pointer.any_released() {
+            app_state.ui_state.resizing_node = None;
+            app_state.end_edit("Redimensionner nœud");
+        }
+    }
+}
+
 // Gère sélection des flèches et désélection dans le vide
 fn handle_arrow_selection(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
     let pointer = &ctx.input(|i| i.pointer.clone());
@@ -134,90 +380,176 @@ fn handle_arrow_selection(ctx: &Context, response: &Response, transform: &Transf
     if response.hovered() && pointer.button_pressed(PointerButton::Primary)
         && !ctx.is_being_dragged(response.id) // Ignore si on drague un nœud
         && app_state.ui_state.arrow_creation_start.is_none() // Ignore si on crée une flèche
+        && app_state.ui_state.resizing_node.is_none() // Poignée pouvant déborder du nœud
     {
         let click_pos_screen = pointer.press_origin().unwrap_or_default();
         let click_pos_world = transform.screen_to_world(click_pos_screen);
-        let mut clicked_on_arrow = None;
-
-        let selection_dist_world_sq = (ARROW_SELECT_DISTANCE / transform.zoom).powi(2);
-
-        for arrow in app_state.diagram.arrows.values() {
-            if let (Some(src_node), Some(tgt_node)) = (
-                app_state.diagram.get_node(arrow.source.node_id),
-                app_state.diagram.get_node(arrow.target.node_id),
-            ) {
-                let start_pos_world = get_connection_pos(src_node, arrow.source.side);
-                let end_pos_world = get_connection_pos(tgt_node, arrow.target.side);
-                if distance_sq_to_segment(click_pos_world, start_pos_world, end_pos_world) < selection_dist_world_sq {
-                    clicked_on_arrow = Some(arrow.id); break;
-                }
-            }
-        }
+        let shift = ctx.input(|i| i.modifiers.shift);
 
-        if let Some(arrow_id) = clicked_on_arrow {
+        if let Some(arrow_id) = arrow_at(app_state, click_pos_world, transform) {
              let clicked_on_conn_point = find_closest_connection_point(&app_state.diagram, click_pos_world, CONNECTION_POINT_RADIUS * 3.0).is_some();
              if !clicked_on_conn_point {
-                app_state.ui_state.selected_arrow = Some(arrow_id);
-                app_state.ui_state.selected_node = None;
+                if shift { app_state.ui_state.selection.toggle_arrow(arrow_id); }
+                else { app_state.ui_state.selection = Selection::arrow(arrow_id); }
                 log::debug!("Flèche sélectionnée: {}", arrow_id);
              } else { log::trace!("Clic sur flèche ignoré (proche point connexion)"); }
         } else {
              let clicked_on_conn_point = find_closest_connection_point(&app_state.diagram, click_pos_world, CONNECTION_POINT_RADIUS * 3.0).is_some();
-             let mut clicked_on_node = false;
-             for node in app_state.diagram.nodes.values() { if node.rect.contains(click_pos_world) { clicked_on_node = true; break; } }
+             let clicked_on_node = node_at(app_state, click_pos_world).is_some();
 
-             if !clicked_on_conn_point && !clicked_on_node {
+             if !clicked_on_conn_point && !clicked_on_node && !shift {
                 log::trace!("Clic détecté dans le vide, désélection.");
-                app_state.ui_state.selected_node = None;
-                app_state.ui_state.selected_arrow = None;
+                app_state.ui_state.selection.clear();
              }
         }
     }
 }
 
-// Gère création de flèches
+// Flèche passant sous une position monde
+fn arrow_at(app_state: &AppState, world_pos: Point, transform: &Transform) -> Option<ArrowId> {
+    let selection_dist_world_sq = (ARROW_SELECT_DISTANCE / transform.zoom).powi(2);
+    app_state.diagram.arrows.values()
+        .find(|arrow| app_state.diagram.arrow_path(arrow).is_some_and(|path| distance_sq_to_path(world_pos, &path) < selection_dist_world_sq))
+        .map(|arrow| arrow.id)
+}
+
+fn node_at(app_state: &AppState, world_pos: Point) -> Option<NodeId> {
+    app_state.diagram.nodes.values().find(|node| node.rect.contains(world_pos)).map(|node| node.id)
+}
+
+// Sélection rectangulaire: glisser depuis le vide sélectionne les éléments entièrement
+// contenus dans le rectangle (Maj: ajoutés à la sélection courante)
+fn handle_rubber_band(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
+    let pointer = &ctx.input(|i| i.pointer.clone());
+
+    if response.hovered() && pointer.button_pressed(PointerButton::Primary) {
+        let ui_state = &app_state.ui_state;
+        let busy = ui_state.dragging_nodes || ui_state.dragging_waypoint.is_some() || ui_state.dragging_slot.is_some()
+            || ui_state.resizing_node.is_some() || ui_state.arrow_creation_start.is_some();
+        let press_pos_world = transform.screen_to_world(pointer.press_origin().unwrap_or_default());
+        if !busy && node_at(app_state, press_pos_world).is_none() && arrow_at(app_state, press_pos_world, transform).is_none() {
+            app_state.ui_state.rubber_band = Some(press_pos_world);
+        }
+    }
+
+    if let Some(start) = app_state.ui_state.rubber_band {
+        if pointer.any_released() {
+            app_state.ui_state.rubber_band = None;
+            let band = WorldRect::from_two_pos(start, app_state.ui_state.mouse_pos);
+            if band.width() > 0.0 || band.height() > 0.0 {
+                app_state.ui_state.selection.extend_in_rect(&app_state.diagram, band);
+                log::debug!("Sélection rectangulaire: {} élément(s)", app_state.ui_state.selection.len());
+            }
+        }
+    }
+}
+
+// Taille de chaque nœud ajustée à son nom (coin supérieur gauche fixe)
+fn fit_nodes_to_labels(diagram: &mut SadtDiagram, ids: &[NodeId]) {
+    for id in ids {
+        if let Some(node) = diagram.get_node_mut(*id) {
+            node.rect = WorldRect::from_min_size(node.rect.min, node.fit_size());
+        }
+    }
+}
+
+// Commandes du presse-papiers sur la sélection (menu contextuel et menu Edition)
+pub fn clipboard_menu(ctx: &Context, ui: &mut Ui, app_state: &mut AppState) {
+    let has_nodes = !app_state.ui_state.selection.nodes.is_empty();
+    if ui.add_enabled(has_nodes, egui::Button::new("Copier").shortcut_text("Ctrl+C")).clicked() {
+        copy_selection(ctx, app_state);
+        ui.close_menu();
+    }
+    if ui.add_enabled(has_nodes, egui::Button::new("Couper").shortcut_text("Ctrl+X")).clicked() {
+        cut_selection(ctx, app_state);
+        ui.close_menu();
+    }
+    if ui.add_enabled(app_state.clipboard.text.is_some(), egui::Button::new("Coller").shortcut_text("Ctrl+V")).clicked() {
+        paste(app_state);
+        ui.close_menu();
+    }
+    if ui.add_enabled(has_nodes, egui::Button::new("Dupliquer").shortcut_text("Ctrl+D")).clicked() {
+        duplicate_selection(app_state);
+        ui.close_menu();
+    }
+}
+
+// Commandes d'alignement et de répartition des nœuds sélectionnés (menu contextuel et menu Edition)
+pub fn alignment_menu(ui: &mut Ui, app_state: &mut AppState) {
+    let nodes = app_state.ui_state.selection.nodes.clone();
+    let alignments = [
+        ("Aligner à gauche", Alignment::Left),
+        ("Centrer horizontalement", Alignment::CenterX),
+        ("Aligner à droite", Alignment::Right),
+        ("Aligner en haut", Alignment::Top),
+        ("Centrer verticalement", Alignment::CenterY),
+        ("Aligner en bas", Alignment::Bottom),
+    ];
+    for (text, alignment) in alignments {
+        if ui.add_enabled(nodes.len() >= 2, egui::Button::new(text)).clicked() {
+            app_state.edit("Aligner", |diagram| align_nodes(diagram, &nodes, alignment));
+            ui.close_menu();
+        }
+    }
+    ui.separator();
+    for (text, horizontal) in [("Répartir horizontalement", true), ("Répartir verticalement", false)] {
+        if ui.add_enabled(nodes.len() >= 3, egui::Button::new(text)).clicked() {
+            app_state.edit("Répartir", |diagram| distribute_nodes(diagram, &nodes, horizontal));
+            ui.close_menu();
+        }
+    }
+}
+
+// Supprime les nœuds (et leurs flèches) et les flèches sélectionnés, en une seule étape d'annulation
+pub fn delete_selection(app_state: &mut AppState) {
+    let selection = &app_state.ui_state.selection;
+    let label = match (selection.single_node(), selection.single_arrow()) {
+        (Some(_), _) => "Supprimer nœud",
+        (_, Some(_)) => "Supprimer flèche",
+        _ => "Supprimer la sélection",
+    };
+    remove_selection(app_state, label);
+}
+
+pub fn remove_selection(app_state: &mut AppState, label: &str) {
+    let selection = std::mem::take(&mut app_state.ui_state.selection);
+    if selection.is_empty() { return; }
+    app_state.edit(label, |diagram| {
+        for id in &selection.arrows { diagram.remove_arrow(*id); }
+        for id in &selection.nodes { diagram.remove_node(*id); }
+    });
+    app_state.ui_state.renaming_node = None; app_state.ui_state.renaming_arrow = None;
+}
+
+// Gère création de flèches (entre nœuds, ou depuis/vers un port ICOM du cadre).
+// Alt+glisser depuis une flèche crée un embranchement; relâcher sur une flèche, une jonction.
 fn handle_arrow_creation(ctx: &Context, response: &Response, transform: &Transform, app_state: &mut AppState) {
     let pointer = &ctx.input(|i| i.pointer.clone());
     let mouse_pos_world = app_state.ui_state.mouse_pos; // Position monde de la souris
 
-    if pointer.button_pressed(PointerButton::Primary) {
+    if pointer.button_pressed(PointerButton::Primary) && app_state.ui_state.dragging_slot.is_none() {
         let click_pos_screen = pointer.press_origin().unwrap_or_default();
         let click_pos_world = transform.screen_to_world(click_pos_screen);
-        if let Some(start_point) = find_closest_connection_point(&app_state.diagram, click_pos_world, CONNECTION_POINT_RADIUS * 3.0) {
+        let from_arrow = ctx.input(|i| i.modifiers.alt) && app_state.ui_state.dragging_waypoint.is_none();
+        let start_end = find_closest_arrow_end(app_state, click_pos_world, CONNECTION_POINT_RADIUS * 3.0)
+            .or_else(|| from_arrow.then(|| arrow_at(app_state, click_pos_world, transform)).flatten().map(ArrowEnd::Junction));
+        if let Some(start_end) = start_end {
             if !ctx.is_being_dragged(response.id) {
-                 app_state.ui_state.selected_node = None;
-                 app_state.ui_state.selected_arrow = None;
-                 app_state.ui_state.arrow_creation_start = Some(start_point.clone());
-                 log::debug!("Début création flèche depuis: {:?}", start_point);
+                 app_state.ui_state.selection.clear();
+                 log::debug!("Début création flèche depuis: {:?}", start_end);
+                 app_state.ui_state.arrow_creation_start = Some(start_end);
             } else { log::trace!("Ignoré début flèche (drag en cours)"); }
         }
     }
 
-    if let Some(start_point) = &app_state.ui_state.arrow_creation_start {
+    if let Some(start_end) = app_state.ui_state.arrow_creation_start.clone() {
         if pointer.any_released() {
              log::debug!("Relâchement détecté, tentative fin flèche à monde {:?}", mouse_pos_world);
-             if let Some(end_point) = find_closest_connection_point(&app_state.diagram, mouse_pos_world, CONNECTION_POINT_RADIUS * 4.0) {
-                if start_point.node_id != end_point.node_id && app_state.diagram.nodes.contains_key(&end_point.node_id) {
-                    let arrow_type = match (start_point.side, end_point.side) {
-                        (Side::Right, Side::Left) => ArrowType::Output,
-                        (Side::Left, Side::Right) => ArrowType::Input,
-                        (_, Side::Top) => ArrowType::Control,
-                        (_, Side::Bottom) => ArrowType::Mechanism,
-                        (Side::Right, _) => ArrowType::Output,
-                        (_, Side::Left) => ArrowType::Input,
-                        _ => ArrowType::Input,
-                    };
-                    log::debug!(
-                        "Fin création flèche vers: {:?}, type: {:?}",
-                        end_point,
-                        arrow_type
-                    );
-                    app_state.diagram.add_arrow(start_point.clone(), end_point, arrow_type, None);
-                } else {
-                    log::debug!("Annulation flèche (même nœud ou cible invalide)");
-                }
-            } else {
-                log::debug!("Annulation flèche (relâchement dans vide)");
+             let end_end = find_closest_arrow_end(app_state, mouse_pos_world, CONNECTION_POINT_RADIUS * 4.0)
+                 .or_else(|| arrow_at(app_state, mouse_pos_world, transform).map(ArrowEnd::Junction));
+             match end_end {
+                Some(end_end) => create_arrow_between(app_state, start_end, end_end),
+                None => log::debug!("Annulation flèche (relâchement dans vide)"),
             }
             app_state.ui_state.arrow_creation_start = None; // Toujours réinitialiser
         } else {
@@ -226,6 +558,90 @@ fn handle_arrow_creation(ctx: &Context, response: &Response, transform: &Transfo
     }
 }
 
+// Point de connexion d'un nœud, ou à défaut port ICOM du cadre, proche d'une position monde
+fn find_closest_arrow_end(app_state: &AppState, world_pos: Point, max_dist_world: f32) -> Option<ArrowEnd> {
+    find_closest_connection_point(&app_state.diagram, world_pos, max_dist_world)
+        .map(ArrowEnd::Node)
+        .or_else(|| {
+            find_closest_boundary_port(&app_state.diagram, &app_state.ui_state.boundary_ports, world_pos, max_dist_world)
+                .map(ArrowEnd::Boundary)
+        })
+}
+
+// Crée la flèche correspondant à un glisser d'une extrémité à l'autre
+fn create_arrow_between(app_state: &mut AppState, start: ArrowEnd, end: ArrowEnd) {
+    match (start, end) {
+        (ArrowEnd::Node(start_point), ArrowEnd::Node(end_point)) => {
+            if start_point.node_id == end_point.node_id {
+                log::debug!("Annulation flèche (même nœud ou cible invalide)");
+                return;
+            }
+            // Glisser à rebours (d'une entrée vers une sortie): la sortie reste la source
+            let (start_point, end_point) = if end_point.side == Side::Right && start_point.side != Side::Right {
+                (end_point, start_point)
+            } else {
+                (start_point, end_point)
+            };
+            let arrow_type = match (start_point.side, end_point.side) {
+                (Side::Right, Side::Left) => ArrowType::Output,
+                (_, Side::Top) => ArrowType::Control,
+                (_, Side::Bottom) => ArrowType::Mechanism,
+                (Side::Right, _) => ArrowType::Output,
+                (_, Side::Left) => ArrowType::Input,
+                _ => ArrowType::Input,
+            };
+            log::debug!("Fin création flèche vers: {:?}, type: {:?}", end_point, arrow_type);
+            app_state.edit("Ajouter flèche", |diagram| diagram.add_arrow(start_point, end_point, arrow_type, None));
+        }
+        // Flèche frontière: le sens est imposé par le code ICOM, quel que soit le sens du glisser
+        (ArrowEnd::Node(point), ArrowEnd::Boundary(code)) | (ArrowEnd::Boundary(code), ArrowEnd::Node(point)) => {
+            let label = app_state.ui_state.boundary_ports.iter()
+                .find(|port| port.code == code)
+                .and_then(|port| port.label.clone());
+            log::debug!("Création flèche frontière {} sur {:?}", code, point);
+            app_state.edit("Ajouter flèche frontière", |diagram| {
+                if code.arrow_type == ArrowType::Output {
+                    diagram.add_arrow(point, ArrowEnd::Boundary(code), code.arrow_type, label)
+                } else {
+                    diagram.add_arrow(ArrowEnd::Boundary(code), point, code.arrow_type, label)
+                }
+            });
+        }
+        (ArrowEnd::Boundary(_), ArrowEnd::Boundary(_)) => {
+            log::debug!("Annulation flèche (deux extrémités sur le cadre)");
+        }
+        // Embranchement vers une entrée, un contrôle ou un mécanisme, ou vers une sortie du cadre;
+        // jonction d'une sortie (ou d'une entrée du cadre) sur un faisceau. Le sens du glisser
+        // n'importe pas: une sortie de boîte reste la source.
+        (ArrowEnd::Junction(id), ArrowEnd::Junction(other)) => {
+            log::debug!("Annulation flèche (deux jonctions: {} et {})", id, other);
+        }
+        (ArrowEnd::Junction(trunk), other) | (other, ArrowEnd::Junction(trunk)) => {
+            let Some(arrow_type) = app_state.diagram.get_arrow(trunk).map(|arrow| arrow.arrow_type) else { return };
+            let joins = match &other {
+                ArrowEnd::Node(point) => point.side == Side::Right,
+                ArrowEnd::Boundary(code) => code.arrow_type != ArrowType::Output,
+                ArrowEnd::Junction(_) => false,
+            };
+            if joins {
+                let arrow_type = other.icom().map_or(arrow_type, |code| code.arrow_type);
+                log::debug!("Jonction de {:?} sur la flèche {}", other, trunk);
+                app_state.edit("Ajouter jonction", |diagram| diagram.add_arrow(other, ArrowEnd::Junction(trunk), arrow_type, None));
+            } else {
+                // Vers une entrée, même type que le tronc s'il sort d'une boîte (comme une flèche
+                // tracée d'une sortie vers une entrée), sinon le rôle du côté atteint
+                let arrow_type = match &other {
+                    ArrowEnd::Node(point) if point.side == Side::Left && arrow_type == ArrowType::Output => ArrowType::Output,
+                    ArrowEnd::Node(point) => point.side.icom_role(),
+                    _ => ArrowType::Output,
+                };
+                log::debug!("Embranchement de la flèche {} vers {:?}", trunk, other);
+                app_state.edit("Ajouter embranchement", |diagram| diagram.add_arrow(ArrowEnd::Junction(trunk), other, arrow_type, None));
+            }
+        }
+    }
+}
+
 // Gère renommage nœuds et flèches
 fn handle_rename(ctx: &Context, transform: &Transform, app_state: &mut AppState) { // Prend Transform
     // Renommage Nœud
@@ -241,13 +657,17 @@ fn handle_rename(ctx: &Context, transform: &Transform, app_state: &mut AppState)
                     if !ctx.memory(|mem| mem.has_focus(text_edit_response.id)) { text_edit_response.request_focus(); }
 
                     let mut close = false; let mut success = false;
-                    if text_edit_response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
-                    else if !text_edit_response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
+                    if ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
                     else if ui.input(|i| i.key_pressed(Key::Escape)) { success = false; close = true; }
                     else if text_edit_response.lost_focus() { success = false; close = true; log::debug!("Annulation renommage nœud (focus perdu)"); }
 
                     if close {
-                        if success { if let Some(n) = app_state.diagram.get_node_mut(node_id) { n.name = app_state.ui_state.renaming_label_text.clone(); log::info!("Nœud renommé"); } }
+                        if success {
+                            let new_name = app_state.ui_state.renaming_label_text.clone();
+                            app_state.edit("Renommer nœud", |diagram| {
+                                if let Some(n) = diagram.get_node_mut(node_id) { n.name = new_name; n.grow_to_label(); log::info!("Nœud renommé"); }
+                            });
+                        }
                         else { log::info!("Renommage nœud annulé"); }
                         app_state.ui_state.renaming_node = None; app_state.ui_state.renaming_label_text.clear();
                     }
@@ -257,9 +677,9 @@ fn handle_rename(ctx: &Context, transform: &Transform, app_state: &mut AppState)
     // Renommage Flèche
     else if let Some(arrow_id) = app_state.ui_state.renaming_arrow {
          if let Some(arrow) = app_state.diagram.get_arrow(arrow_id) {
-             let mid_pos_world = if let (Some(src), Some(tgt)) = (app_state.diagram.get_node(arrow.source.node_id), app_state.diagram.get_node(arrow.target.node_id)) {
-                 get_connection_pos(src, arrow.source.side).lerp(get_connection_pos(tgt, arrow.target.side), 0.5)
-             } else { Pos2::ZERO };
+             let mid_pos_world = app_state.diagram.arrow_path(arrow)
+                 .map(|path| path_midpoint(&path))
+                 .unwrap_or(Point::ZERO);
              let mid_pos_screen = transform.world_to_screen(mid_pos_world); // Position écran
 
              egui::Window::new("Editer Label Flèche")
@@ -270,13 +690,17 @@ fn handle_rename(ctx: &Context, transform: &Transform, app_state: &mut AppState)
                     if !ctx.memory(|mem| mem.has_focus(text_edit_response.id)) { text_edit_response.request_focus(); }
 
                     let mut close = false; let mut success = false;
-                    if text_edit_response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
-                    else if !text_edit_response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
+                    if ui.input(|i| i.key_pressed(Key::Enter)) { success = true; close = true; }
                     else if ui.input(|i| i.key_pressed(Key::Escape)) { success = false; close = true; }
                     else if text_edit_response.lost_focus() { success = false; close = true; log::debug!("Annulation edit label (focus perdu)"); }
 
                     if close {
-                        if success { if let Some(a) = app_state.diagram.arrows.get_mut(&arrow_id) { let lbl = app_state.ui_state.renaming_label_text.clone(); a.label = if lbl.is_empty() { None } else { Some(lbl) }; log::info!("Label flèche édité"); } }
+                        if success {
+                            let lbl = app_state.ui_state.renaming_label_text.clone();
+                            app_state.edit("Editer label flèche", |diagram| {
+                                if let Some(a) = diagram.arrows.get_mut(&arrow_id) { a.label = if lbl.is_empty() { None } else { Some(lbl) }; log::info!("Label flèche édité"); }
+                            });
+                        }
                         else { log::info!("Edition label flèche annulée"); }
                         app_state.ui_state.renaming_arrow = None; app_state.ui_state.renaming_label_text.clear();
                     }
@@ -285,17 +709,27 @@ fn handle_rename(ctx: &Context, transform: &Transform, app_state: &mut AppState)
     }
 }
 
+// Copier (Ctrl+C), couper (Ctrl+X), coller (Ctrl+V) et dupliquer (Ctrl+D), hors saisie de texte
+fn handle_clipboard(ctx: &Context, app_state: &mut AppState) {
+    if ctx.memory(|mem| mem.focused().is_some()) { return; }
+    for event in ctx.input(|i| i.events.clone()) {
+        match event {
+            egui::Event::Copy => { copy_selection(ctx, app_state); }
+            egui::Event::Cut => cut_selection(ctx, app_state),
+            egui::Event::Paste(text) => paste_text(app_state, &text),
+            _ => {}
+        }
+    }
+    if ctx.input_mut(|i| i.consume_key(Modifiers::COMMAND, Key::D)) {
+        duplicate_selection(app_state);
+    }
+}
+
 // Gère suppression via clavier
 fn handle_deletion(ctx: &Context, app_state: &mut AppState) {
+    if ctx.memory(|mem| mem.focused().is_some()) { return; } // Touches laissées au champ texte (panneau des propriétés...)
     if ctx.input(|i| i.key_pressed(Key::Delete)) || ctx.input(|i| i.key_pressed(Key::Backspace)) {
-        if let Some(node_id) = app_state.ui_state.selected_node.take() {
-             log::info!("Suppression noeud via clavier: {}", node_id);
-            app_state.diagram.remove_node(node_id);
-            app_state.ui_state.renaming_node = None; app_state.ui_state.renaming_arrow = None;
-        } else if let Some(arrow_id) = app_state.ui_state.selected_arrow.take() {
-             log::info!("Suppression flèche via clavier: {}", arrow_id);
-             app_state.diagram.remove_arrow(arrow_id);
-             app_state.ui_state.renaming_arrow = None; app_state.ui_state.renaming_node = None;
-        }
+        log::info!("Suppression via clavier: {} élément(s)", app_state.ui_state.selection.len());
+        delete_selection(app_state);
     }
 }
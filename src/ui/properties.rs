@@ -0,0 +1,362 @@
+// src/ui/properties.rs
+// Panneau des propriétés de la sélection: nœud, flèche ou, sans sélection, diagramme affiché.
+// Les modifications s'appliquent en direct. Une saisie continue (frappe, glisser d'une valeur,
+// nuancier ouvert) ne crée qu'une étape d'annulation, enregistrée quand plus aucun champ
+// du panneau n'est actif.
+use crate::app::AppState;
+use egui::{Color32, Context, Response, RichText, Ui};
+use rustsadt_core::algorithms::{find_algorithm, ALGORITHMS};
+use rustsadt_core::geometry::{vec2, Rect};
+use rustsadt_core::numbering::box_numbers;
+use rustsadt_core::sadt_elements::{ArrowEnd, ArrowId, ArrowType, NodeId, Side};
+use rustsadt_core::sadt_model::{DiagramMetadata, DiagramStatus};
+use rustsadt_core::style::{Color, NODE_FILL};
+
+const MAX_BOX_NUMBER: u32 = 99;
+
+// Bord de la fenêtre où le panneau est ancré
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PanelDock {
+    Left,
+    #[default]
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct PropertiesPanel {
+    pub visible: bool,
+    pub dock: PanelDock,
+    editing: Option<&'static str>, // Étape d'annulation en cours (libellé de la première modification)
+}
+
+impl Default for PropertiesPanel {
+    fn default() -> Self {
+        PropertiesPanel { visible: true, dock: PanelDock::default(), editing: None }
+    }
+}
+
+// Champs du panneau pendant une frame: modification faite, champ encore actif
+#[derive(Default)]
+struct LiveEdit {
+    changed: Option<&'static str>,
+    active: bool,
+}
+
+impl LiveEdit {
+    fn track(&mut self, response: &Response, label: &'static str) {
+        if response.has_focus() || response.dragged() {
+            self.active = true;
+        }
+        if response.changed() {
+            self.changed.get_or_insert(label);
+        }
+    }
+}
+
+// Copie modifiable des propriétés d'un nœud
+#[derive(Clone, PartialEq)]
+struct NodeDraft {
+    name: String,
+    algorithm: String,
+    description: String,
+    number: Option<u32>,
+    fill: Option<Color>,
+    rect: Rect,
+}
+
+// Copie modifiable des propriétés d'une flèche (côtés: extrémités sur une boîte seulement)
+#[derive(Clone, PartialEq)]
+struct ArrowDraft {
+    label: String,
+    arrow_type: ArrowType,
+    data_type: Option<String>,
+    source_side: Option<Side>,
+    target_side: Option<Side>,
+}
+
+pub fn show_properties_panel(ctx: &Context, app_state: &mut AppState) {
+    if !app_state.properties.visible {
+        finish_edit(ctx, app_state, false);
+        return;
+    }
+    let panel = match app_state.properties.dock {
+        PanelDock::Left => egui::SidePanel::left("properties_panel"),
+        PanelDock::Right => egui::SidePanel::right("properties_panel"),
+    };
+    let mut live = LiveEdit::default();
+    panel.resizable(true).default_width(260.0).show(ctx, |ui| {
+        ui.label(RichText::new("Propriétés").strong());
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            let selection = app_state.ui_state.selection.clone();
+            if let Some(node_id) = selection.single_node() {
+                node_properties(ui, app_state, node_id, &mut live);
+            } else if let Some(arrow_id) = selection.single_arrow() {
+                arrow_properties(ui, app_state, arrow_id, &mut live);
+            } else if selection.is_empty() {
+                diagram_properties(ui, app_state, &mut live);
+            } else {
+                ui.label(format!("{} éléments sélectionnés", selection.len()));
+            }
+        });
+    });
+    if let Some(label) = live.changed {
+        app_state.properties.editing.get_or_insert(label);
+    }
+    finish_edit(ctx, app_state, live.active);
+}
+
+// Enregistre l'étape d'annulation du panneau quand la saisie est terminée
+fn finish_edit(ctx: &Context, app_state: &mut AppState, active: bool) {
+    if active || ctx.memory(|mem| mem.any_popup_open()) { return; }
+    if let Some(label) = app_state.properties.editing.take() {
+        app_state.end_edit(label);
+    }
+}
+
+fn node_properties(ui: &mut Ui, app_state: &mut AppState, node_id: NodeId, live: &mut LiveEdit) {
+    let Some(node) = app_state.diagram.get_node(node_id) else { return; };
+    let original = NodeDraft {
+        name: node.name.clone(),
+        algorithm: node.algorithm.clone(),
+        description: node.description.clone(),
+        number: node.number,
+        fill: node.fill,
+        rect: node.rect,
+    };
+    let issues = find_algorithm(&original.algorithm)
+        .map(|algorithm| algorithm.signature_issues(&app_state.diagram, app_state.root_dictionary(), node))
+        .unwrap_or_default();
+    let automatic_number = box_numbers(&app_state.diagram).get(&node_id).copied().unwrap_or(1);
+    let min_width = node.min_size(0.0).x;
+    let min_height = node.min_size(original.rect.width()).y;
+    let mut draft = original.clone();
+
+    ui.label(RichText::new("Activité").strong());
+    egui::Grid::new("node_properties").num_columns(2).show(ui, |ui| {
+        ui.label("Nom:");
+        live.track(&ui.text_edit_singleline(&mut draft.name), "Renommer nœud");
+        ui.end_row();
+
+        // Un nœud sans numéro (fichier antérieur à la numérotation) affiche son numéro
+        // automatique, fixé dès qu'il est modifié
+        ui.label("Numéro:");
+        let mut number = draft.number.unwrap_or(automatic_number);
+        let response = ui.add(egui::DragValue::new(&mut number).clamp_range(1..=MAX_BOX_NUMBER));
+        live.track(&response, "Numéroter nœud");
+        if response.changed() { draft.number = Some(number); }
+        ui.end_row();
+
+        ui.label("Référence:");
+        ui.label(app_state.diagram_reference().box_reference(draft.number.unwrap_or(automatic_number)));
+        ui.end_row();
+
+        ui.label("Couleur:");
+        ui.horizontal(|ui| {
+            let mut colored = draft.fill.is_some();
+            live.track(&ui.checkbox(&mut colored, ""), "Colorer nœud");
+            let fill = draft.fill.unwrap_or(NODE_FILL);
+            let mut rgb = [fill.r, fill.g, fill.b];
+            if colored {
+                live.track(&egui::color_picker::color_edit_button_srgb(ui, &mut rgb), "Colorer nœud");
+            }
+            draft.fill = colored.then_some(Color::rgb(rgb[0], rgb[1], rgb[2]));
+        });
+        ui.end_row();
+
+        ui.label("Taille:");
+        ui.horizontal(|ui| {
+            let (mut width, mut height) = (draft.rect.width(), draft.rect.height());
+            let width_response = ui.add(egui::DragValue::new(&mut width).clamp_range(min_width..=f32::MAX).prefix("l "));
+            let height_response = ui.add(egui::DragValue::new(&mut height).clamp_range(min_height..=f32::MAX).prefix("h "));
+            if width_response.changed() || height_response.changed() {
+                draft.rect = Rect::from_min_size(draft.rect.min, vec2(width, height));
+            }
+            live.track(&width_response, "Redimensionner nœud");
+            live.track(&height_response, "Redimensionner nœud");
+        });
+        ui.end_row();
+    });
+
+    ui.label("Description:");
+    live.track(&ui.add(egui::TextEdit::multiline(&mut draft.description).desired_rows(3).desired_width(f32::INFINITY)), "Décrire nœud");
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Algorithme:");
+        let selected_text = if draft.algorithm.is_empty() { "(aucun)" } else { draft.algorithm.as_str() };
+        egui::ComboBox::from_id_source("node_algorithm")
+            .selected_text(selected_text.to_string())
+            .show_ui(ui, |ui| {
+                live.track(&ui.selectable_value(&mut draft.algorithm, String::new(), "(aucun)"), "Choisir algorithme");
+                for algorithm in ALGORITHMS {
+                    let response = ui.selectable_value(&mut draft.algorithm, algorithm.name.to_string(), algorithm.name)
+                        .on_hover_text(algorithm.description);
+                    live.track(&response, "Choisir algorithme");
+                }
+            });
+    });
+    match find_algorithm(&original.algorithm) {
+        Some(algorithm) => {
+            ui.label(RichText::new(algorithm.signature()).monospace());
+            ui.label(algorithm.description);
+        }
+        None if !original.algorithm.is_empty() => {
+            ui.label(RichText::new(format!("✖ Algorithme inconnu: \"{}\"", original.algorithm)).color(Color32::LIGHT_RED));
+        }
+        None => {
+            ui.label("Le code généré pour cette activité reste à écrire.");
+        }
+    }
+    for issue in &issues {
+        ui.label(RichText::new(format!("⚠ {}", issue)).color(Color32::YELLOW));
+    }
+
+    if draft != original {
+        app_state.begin_edit();
+        if let Some(node) = app_state.diagram.get_node_mut(node_id) {
+            node.name = draft.name;
+            node.algorithm = draft.algorithm;
+            node.description = draft.description;
+            node.number = draft.number;
+            node.fill = draft.fill;
+            node.rect = draft.rect;
+            node.grow_to_label();
+        }
+    }
+}
+
+fn arrow_properties(ui: &mut Ui, app_state: &mut AppState, arrow_id: ArrowId, live: &mut LiveEdit) {
+    let Some(arrow) = app_state.diagram.get_arrow(arrow_id) else { return; };
+    let side = |end: &ArrowEnd| end.connection_point().map(|cp| cp.side);
+    let original = ArrowDraft {
+        label: arrow.label.clone().unwrap_or_default(),
+        arrow_type: arrow.arrow_type,
+        data_type: arrow.data_type.clone(),
+        source_side: side(&arrow.source),
+        target_side: side(&arrow.target),
+    };
+    let end_names = [(&arrow.source, "Embranchement de"), (&arrow.target, "Jonction à")].map(|(end, junction)| match end {
+        ArrowEnd::Node(cp) => app_state.diagram.get_node(cp.node_id).map_or_else(|| "?".to_string(), |node| node.name.clone()),
+        ArrowEnd::Boundary(code) => format!("Bord du cadre ({})", code),
+        ArrowEnd::Junction(id) => {
+            let label = app_state.diagram.get_arrow(*id).and_then(|trunk| trunk.label.clone()).unwrap_or_else(|| "sans label".to_string());
+            format!("{} \"{}\"", junction, label)
+        }
+    });
+    // Type inconnu du dictionnaire: reste proposé pour pouvoir le retirer
+    let mut data_types = vec![None];
+    data_types.extend(app_state.root_dictionary().types.iter().map(|t| Some(t.name.clone())));
+    if original.data_type.is_some() && !data_types.contains(&original.data_type) {
+        data_types.push(original.data_type.clone());
+    }
+    let mut draft = original.clone();
+
+    ui.label(RichText::new("Flèche").strong());
+    egui::Grid::new("arrow_properties").num_columns(2).show(ui, |ui| {
+        ui.label("Label:");
+        live.track(&ui.text_edit_singleline(&mut draft.label), "Editer label flèche");
+        ui.end_row();
+
+        ui.label("Rôle:");
+        egui::ComboBox::from_id_source("arrow_type")
+            .selected_text(draft.arrow_type.label())
+            .show_ui(ui, |ui| {
+                for arrow_type in ArrowType::ALL {
+                    live.track(&ui.selectable_value(&mut draft.arrow_type, arrow_type, arrow_type.label()), "Changer rôle flèche");
+                }
+            });
+        ui.end_row();
+
+        ui.label("Type de donnée:");
+        egui::ComboBox::from_id_source("arrow_data_type")
+            .selected_text(draft.data_type.clone().unwrap_or_else(|| "(aucun)".to_string()))
+            .show_ui(ui, |ui| {
+                for choice in data_types {
+                    let text = choice.clone().unwrap_or_else(|| "(aucun)".to_string());
+                    live.track(&ui.selectable_value(&mut draft.data_type, choice, text), "Typer flèche");
+                }
+            });
+        ui.end_row();
+
+        for (index, (name, side)) in [("Source:", &mut draft.source_side), ("Cible:", &mut draft.target_side)].into_iter().enumerate() {
+            ui.label(name);
+            ui.vertical(|ui| {
+                ui.label(&end_names[index]);
+                if let Some(side) = side {
+                    egui::ComboBox::from_id_source(("arrow_end_side", index))
+                        .selected_text(side.label())
+                        .show_ui(ui, |ui| {
+                            for choice in Side::ALL {
+                                live.track(&ui.selectable_value(side, choice, choice.label()), "Changer côté flèche");
+                            }
+                        });
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if app_state.root_dictionary().is_empty() {
+        ui.label("Dictionnaire vide (Edition > Dictionnaire de données...)");
+    }
+
+    if draft != original {
+        app_state.begin_edit();
+        let diagram = &mut app_state.diagram;
+        if let Some(arrow) = diagram.arrows.get_mut(&arrow_id) {
+            arrow.label = Some(draft.label).filter(|label| !label.is_empty());
+            arrow.arrow_type = draft.arrow_type;
+            arrow.data_type = draft.data_type;
+        }
+        for (source, side) in [(true, draft.source_side), (false, draft.target_side)] {
+            if let Some(side) = side { diagram.set_end_side(arrow_id, source, side); }
+        }
+    }
+}
+
+fn diagram_properties(ui: &mut Ui, app_state: &mut AppState, live: &mut LiveEdit) {
+    let original = app_state.diagram.metadata.clone();
+    let mut draft: DiagramMetadata = original.clone();
+
+    ui.label(RichText::new("Diagramme").strong());
+    ui.label(app_state.breadcrumb());
+    ui.label(format!("{} activité(s), {} flèche(s)", app_state.diagram.nodes.len(), app_state.diagram.arrows.len()));
+    // Référence laissée vide: celle déduite de la hiérarchie, affichée en grisé
+    let computed_reference = app_state.diagram_reference().node;
+    egui::Grid::new("diagram_properties").num_columns(2).show(ui, |ui| {
+        ui.label("Titre:");
+        live.track(&ui.text_edit_singleline(&mut draft.title), "Modifier métadonnées");
+        ui.end_row();
+        ui.label("Nœud:");
+        live.track(&ui.add(egui::TextEdit::singleline(&mut draft.node_reference).hint_text(computed_reference)), "Modifier métadonnées");
+        ui.end_row();
+        let fields = [
+            ("Auteur:", &mut draft.author),
+            ("Projet:", &mut draft.project),
+            ("Date:", &mut draft.date),
+            ("Révision:", &mut draft.revision),
+            ("Numéro C:", &mut draft.c_number),
+        ];
+        for (name, value) in fields {
+            ui.label(name);
+            live.track(&ui.text_edit_singleline(value), "Modifier métadonnées");
+            ui.end_row();
+        }
+        ui.label("Statut:");
+        egui::ComboBox::from_id_source("diagram_status")
+            .selected_text(draft.status.label())
+            .show_ui(ui, |ui| {
+                for status in DiagramStatus::ALL {
+                    live.track(&ui.selectable_value(&mut draft.status, status, status.label()), "Changer statut diagramme");
+                }
+            });
+        ui.end_row();
+    });
+    ui.label("Description:");
+    live.track(&ui.add(egui::TextEdit::multiline(&mut draft.description).desired_rows(3).desired_width(f32::INFINITY)), "Modifier métadonnées");
+
+    if draft != original {
+        app_state.begin_edit();
+        app_state.diagram.metadata = draft;
+    }
+}
@@ -0,0 +1,102 @@
+// src/ui/selection.rs
+// Éléments sélectionnés sur le canvas (clic, Maj+clic, sélection rectangulaire).
+// Les actions sur un seul élément (renommer, propriétés, points de passage) ne
+// s'appliquent que si la sélection se réduit à cet élément.
+use rustsadt_core::geometry::Rect;
+use rustsadt_core::sadt_elements::{ArrowId, NodeId};
+use rustsadt_core::sadt_model::SadtDiagram;
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Selection {
+    pub nodes: Vec<NodeId>,
+    pub arrows: Vec<ArrowId>,
+}
+
+impl Selection {
+    pub fn node(id: NodeId) -> Self {
+        Selection { nodes: vec![id], arrows: Vec::new() }
+    }
+
+    pub fn arrow(id: ArrowId) -> Self {
+        Selection { nodes: Vec::new(), arrows: vec![id] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.arrows.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len() + self.arrows.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.arrows.clear();
+    }
+
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        self.nodes.contains(&id)
+    }
+
+    pub fn contains_arrow(&self, id: ArrowId) -> bool {
+        self.arrows.contains(&id)
+    }
+
+    // Le nœud sélectionné, s'il est seul dans la sélection
+    pub fn single_node(&self) -> Option<NodeId> {
+        match (self.nodes.as_slice(), self.arrows.is_empty()) {
+            ([id], true) => Some(*id),
+            _ => None,
+        }
+    }
+
+    // La flèche sélectionnée, si elle est seule dans la sélection
+    pub fn single_arrow(&self) -> Option<ArrowId> {
+        match (self.arrows.as_slice(), self.nodes.is_empty()) {
+            ([id], true) => Some(*id),
+            _ => None,
+        }
+    }
+
+    // Maj+clic: ajoute ou retire un élément
+    pub fn toggle_node(&mut self, id: NodeId) {
+        match self.nodes.iter().position(|n| *n == id) {
+            Some(index) => { self.nodes.remove(index); }
+            None => self.nodes.push(id),
+        }
+    }
+
+    pub fn toggle_arrow(&mut self, id: ArrowId) {
+        match self.arrows.iter().position(|a| *a == id) {
+            Some(index) => { self.arrows.remove(index); }
+            None => self.arrows.push(id),
+        }
+    }
+
+    // Ajoute les nœuds et les flèches entièrement contenus dans le rectangle
+    pub fn extend_in_rect(&mut self, diagram: &SadtDiagram, rect: Rect) {
+        let mut nodes: Vec<_> = diagram.nodes.values()
+            .filter(|node| rect.contains(node.rect.min) && rect.contains(node.rect.max))
+            .map(|node| node.id)
+            .collect();
+        let mut arrows: Vec<_> = diagram.arrows.values()
+            .filter(|arrow| diagram.arrow_path(arrow).is_some_and(|path| path.iter().all(|p| rect.contains(*p))))
+            .map(|arrow| arrow.id)
+            .collect();
+        // Ordre stable d'une image à l'autre (les HashMap du diagramme n'en ont pas)
+        nodes.sort();
+        arrows.sort();
+        for id in nodes {
+            if !self.contains_node(id) { self.nodes.push(id); }
+        }
+        for id in arrows {
+            if !self.contains_arrow(id) { self.arrows.push(id); }
+        }
+    }
+
+    // Retire les éléments disparus du diagramme (après une annulation ou une suppression)
+    pub fn retain_existing(&mut self, diagram: &SadtDiagram) {
+        self.nodes.retain(|id| diagram.nodes.contains_key(id));
+        self.arrows.retain(|id| diagram.arrows.contains_key(id));
+    }
+}
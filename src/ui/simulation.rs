@@ -0,0 +1,149 @@
+// src/ui/simulation.rs
+// Fenêtre de simulation du diagramme affiché: saisie des entrées, exécution complète
+// ou pas à pas. Les valeurs et l'activité exécutée sont reportées sur le canvas.
+use crate::app::AppState;
+use crate::ui::drawing::SimulationOverlay;
+use crate::ui::selection::Selection;
+use egui::{Color32, Context, RichText};
+use rustsadt_core::sadt_elements::{ArrowId, NodeId};
+use rustsadt_core::sadt_model::{Arrow, SadtDiagram};
+use rustsadt_core::simulation::{external_inputs, external_outputs, Simulation};
+use rustsadt_core::validation::DiagnosticElement;
+use std::collections::HashMap;
+
+#[derive(Default)]
+pub struct SimulationPanel {
+    inputs: HashMap<ArrowId, f64>, // Valeurs saisies, conservées entre deux exécutions
+    run: Option<SimulationRun>,
+    error: Option<String>, // Simulation impossible (cycle)
+}
+
+struct SimulationRun {
+    simulation: Simulation,
+    diagram: SadtDiagram, // Diagramme simulé: toute modification interrompt la simulation
+    last_node: Option<NodeId>,
+}
+
+impl SimulationPanel {
+    fn start(&mut self, diagram: &SadtDiagram) -> Option<&mut SimulationRun> {
+        match Simulation::new(diagram, &self.inputs) {
+            Ok(simulation) => {
+                self.error = None;
+                self.run = Some(SimulationRun { simulation, diagram: diagram.clone(), last_node: None });
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                self.run = None;
+            }
+        }
+        self.run.as_mut()
+    }
+}
+
+pub fn show_simulation_window(ctx: &Context, app_state: &mut AppState) {
+    let Some(mut panel) = app_state.simulation.take() else {
+        app_state.ui_state.simulation = None;
+        return;
+    };
+    if panel.run.as_ref().is_some_and(|run| run.diagram != app_state.diagram) {
+        log::info!("Diagramme modifié: simulation interrompue");
+        panel.run = None;
+    }
+
+    let diagram = &app_state.diagram;
+    let dictionary = app_state.root_dictionary();
+    let mut is_open = true;
+    let mut selected = None;
+    egui::Window::new("Simulation")
+        .open(&mut is_open)
+        .default_width(300.0)
+        .show(ctx, |ui| {
+            ui.label(RichText::new("Entrées").strong());
+            let inputs = external_inputs(diagram);
+            if inputs.is_empty() {
+                ui.label("Aucune flèche d'entrée sur le cadre du diagramme.");
+            }
+            egui::Grid::new("simulation_inputs").num_columns(2).show(ui, |ui| {
+                for arrow in inputs {
+                    ui.label(arrow_title(arrow));
+                    ui.add(egui::DragValue::new(panel.inputs.entry(arrow.id).or_insert(0.0)).speed(0.1));
+                    ui.end_row();
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("▶ Exécuter").clicked() {
+                    if let Some(run) = panel.start(diagram) {
+                        run.simulation.run(diagram, dictionary);
+                        run.last_node = None;
+                    }
+                }
+                let can_step = panel.run.as_ref().is_none_or(|run| !run.simulation.is_finished());
+                if ui.add_enabled(can_step, egui::Button::new("⏭ Pas suivant")).clicked() {
+                    // Le premier pas démarre la simulation sans exécuter d'activité
+                    match panel.run.as_mut() {
+                        Some(run) => run.last_node = run.simulation.step(diagram, dictionary),
+                        None => { panel.start(diagram); }
+                    }
+                }
+                if ui.button("⟲ Réinitialiser").clicked() {
+                    panel.run = None;
+                    panel.error = None;
+                }
+            });
+            if let Some(error) = &panel.error {
+                ui.label(RichText::new(format!("✖ {}", error)).color(Color32::LIGHT_RED));
+            }
+
+            let Some(run) = &panel.run else { return; };
+            let status = match run.simulation.next_node().and_then(|id| diagram.get_node(id)) {
+                Some(node) => format!("Prochaine activité: {}", node.name),
+                None => "Simulation terminée".to_string(),
+            };
+            ui.label(status);
+
+            ui.separator();
+            ui.label(RichText::new("Sorties").strong());
+            for arrow in external_outputs(diagram) {
+                let value = run.simulation.value(arrow.id).map_or("—".to_string(), |v| v.to_string());
+                ui.label(format!("{} = {}", arrow_title(arrow), value));
+            }
+
+            let diagnostics = run.simulation.diagnostics();
+            if !diagnostics.is_empty() {
+                ui.separator();
+                for diagnostic in diagnostics {
+                    let text = RichText::new(format!("✖ {}", diagnostic.message)).color(Color32::LIGHT_RED);
+                    if ui.selectable_label(false, text).clicked() {
+                        if let DiagnosticElement::Node(id) = diagnostic.element { selected = Some(id); }
+                    }
+                }
+            }
+        });
+
+    app_state.ui_state.simulation = panel.run.as_ref().map(|run| SimulationOverlay {
+        values: run.simulation.values().clone(),
+        active_node: run.last_node,
+        error_nodes: run.simulation.diagnostics().iter()
+            .filter_map(|d| match d.element { DiagnosticElement::Node(id) => Some(id), _ => None })
+            .collect(),
+    });
+    if let Some(node_id) = selected {
+        app_state.ui_state.selection = Selection::node(node_id);
+    }
+    if is_open {
+        app_state.simulation = Some(panel);
+    } else {
+        app_state.ui_state.simulation = None;
+    }
+}
+
+// "I1 Données" pour une flèche frontière
+fn arrow_title(arrow: &Arrow) -> String {
+    let code = arrow.icom().map(|code| code.to_string()).unwrap_or_default();
+    match &arrow.label {
+        Some(label) => format!("{} {}", code, label),
+        None => code,
+    }
+}
@@ -0,0 +1,76 @@
+// src/ui/snapping.rs
+// Grille du canvas, magnétisme et guides d'alignement. Pendant un déplacement, un bord
+// ou le centre du groupe déplacé s'aligne d'abord sur celui d'un nœud voisin proche
+// (une ligne guide le montre), sinon le coin du groupe s'accroche à la grille.
+use rustsadt_core::geometry::{Point, Rect, Vec2};
+
+pub const DEFAULT_GRID_SPACING: f32 = 20.0; // Pas monde de la grille
+const GUIDE_DISTANCE: f32 = 6.0; // Distance écran en deçà de laquelle on s'aligne sur un voisin
+const ALIGNED: f32 = 0.5; // Écart monde toléré pour afficher un guide
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridSettings {
+    pub visible: bool, // Grille dessinée en fond du canvas
+    pub snap: bool,    // Positions et tailles accrochées à la grille (Alt: désactivé le temps d'un geste)
+    pub guides: bool,  // Alignement sur les nœuds voisins, avec lignes guides
+    pub spacing: f32,
+}
+
+impl Default for GridSettings {
+    fn default() -> Self {
+        GridSettings { visible: false, snap: false, guides: true, spacing: DEFAULT_GRID_SPACING }
+    }
+}
+
+impl GridSettings {
+    pub fn snap_value(&self, value: f32) -> f32 {
+        (value / self.spacing).round() * self.spacing
+    }
+
+    pub fn snap_point(&self, p: Point) -> Point {
+        Point::new(self.snap_value(p.x), self.snap_value(p.y))
+    }
+}
+
+// Ligne guide temporaire, en coordonnées monde
+pub type Guide = (Point, Point);
+
+// Correction à appliquer au rectangle déplacé (alignement sur `others`, sinon grille)
+// et guides des alignements obtenus
+pub fn snap_moved_rect(rect: Rect, others: &[Rect], settings: &GridSettings, zoom: f32) -> (Vec2, Vec<Guide>) {
+    let max_dist = GUIDE_DISTANCE / zoom;
+    let xs = |r: &Rect| [r.left(), r.center().x, r.right()];
+    let ys = |r: &Rect| [r.top(), r.center().y, r.bottom()];
+
+    let neighbours = if settings.guides { others } else { &[] };
+    let axis_correction = |moving: [f32; 3], targets: &mut dyn Iterator<Item = [f32; 3]>, start: f32| {
+        let best = targets
+            .flat_map(|target| target.into_iter().flat_map(move |t| moving.into_iter().map(move |m| t - m)))
+            .filter(|d| d.abs() <= max_dist)
+            .min_by(|a, b| a.abs().total_cmp(&b.abs()));
+        match best {
+            Some(d) => d,
+            None if settings.snap => settings.snap_value(start) - start,
+            None => 0.0,
+        }
+    };
+    let dx = axis_correction(xs(&rect), &mut neighbours.iter().map(xs), rect.left());
+    let dy = axis_correction(ys(&rect), &mut neighbours.iter().map(ys), rect.top());
+    let snapped = rect.translate(Vec2::new(dx, dy));
+
+    // Un guide par alignement exact, tracé d'un rectangle à l'autre
+    let mut guides = Vec::new();
+    for other in neighbours {
+        for x in xs(&snapped) {
+            if xs(other).iter().any(|o| (o - x).abs() < ALIGNED) {
+                guides.push((Point::new(x, snapped.top().min(other.top())), Point::new(x, snapped.bottom().max(other.bottom()))));
+            }
+        }
+        for y in ys(&snapped) {
+            if ys(other).iter().any(|o| (o - y).abs() < ALIGNED) {
+                guides.push((Point::new(snapped.left().min(other.left()), y), Point::new(snapped.right().max(other.right()), y)));
+            }
+        }
+    }
+    (Vec2::new(dx, dy), guides)
+}
@@ -1,11 +1,13 @@
 // src/ui/transform.rs
+// Passage des coordonnées monde (types de `rustsadt-core`) aux coordonnées écran (types egui)
 use egui::{Pos2, Rect, Vec2};
+use rustsadt_core::geometry::{Point, Rect as WorldRect, Vec2 as WorldVec2};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Transform {
     // Pan (translation) in world coordinates.
     // Represents the world coordinate directly under the top-left corner (0,0) of the screen viewport.
-    pub pan: Vec2,
+    pub pan: WorldVec2,
     // Zoom factor. Higher value means zoomed in.
     pub zoom: f32,
     // The screen viewport rectangle (needed for some calculations, though not strictly part of the transform itself).
@@ -15,31 +17,28 @@ pub struct Transform {
 
 impl Transform {
     // Pass pan and zoom. screen_center is removed.
-    pub fn new(pan: Vec2, zoom: f32 /*, screen_center: Pos2 */) -> Self {
+    pub fn new(pan: WorldVec2, zoom: f32 /*, screen_center: Pos2 */) -> Self {
         Self { pan, zoom /*, screen_center */ }
     }
 
     /// Converts screen coordinates to world coordinates.
     #[inline]
-    pub fn screen_to_world(&self, screen_pos: Pos2) -> Pos2 {
+    pub fn screen_to_world(&self, screen_pos: Pos2) -> Point {
         // World position = Pan + (Screen Position / Zoom Factor)
-        // Addition Vec2 + Vec2 -> Vec2. Conversion en Pos2.
-        Pos2::new(self.pan.x + screen_pos.x / self.zoom, self.pan.y + screen_pos.y / self.zoom)
+        Point::new(self.pan.x + screen_pos.x / self.zoom, self.pan.y + screen_pos.y / self.zoom)
     }
 
     /// Converts world coordinates to screen coordinates.
     #[inline]
-    pub fn world_to_screen(&self, world_pos: Pos2) -> Pos2 {
+    pub fn world_to_screen(&self, world_pos: Point) -> Pos2 {
         // Screen position = (World Position - Pan) * Zoom Factor
-        // Soustraction Pos2 - Vec2 -> Pos2. Multiplication Pos2 * f32 n'existe pas.
-        // Faire : (world_pos.to_vec2() - self.pan) * self.zoom -> Vec2, puis convertir en Pos2
         let screen_vec = (world_pos.to_vec2() - self.pan) * self.zoom;
         Pos2::new(screen_vec.x, screen_vec.y)
     }
 
     /// Converts world rectangle to screen rectangle.
     #[inline]
-    pub fn world_rect_to_screen(&self, world_rect: Rect) -> Rect {
+    pub fn world_rect_to_screen(&self, world_rect: WorldRect) -> Rect {
         Rect::from_min_max(
             self.world_to_screen(world_rect.min),
             self.world_to_screen(world_rect.max),
@@ -49,14 +48,15 @@ impl Transform {
      /// Converts screen vector (like a mouse delta) to world vector.
      /// For vectors, only scaling applies, not panning.
      #[inline]
-    pub fn screen_vec_to_world(&self, screen_vec: Vec2) -> Vec2 {
-        screen_vec / self.zoom
+    pub fn screen_vec_to_world(&self, screen_vec: Vec2) -> WorldVec2 {
+        WorldVec2::new(screen_vec.x, screen_vec.y) / self.zoom
     }
 
     /// Converts world vector to screen vector.
     /// For vectors, only scaling applies, not panning.
     #[inline]
-    pub fn world_vec_to_screen(&self, world_vec: Vec2) -> Vec2 {
-        world_vec * self.zoom
+    #[allow(dead_code)]
+    pub fn world_vec_to_screen(&self, world_vec: WorldVec2) -> Vec2 {
+        Vec2::new(world_vec.x, world_vec.y) * self.zoom
     }
 }
\ No newline at end of file